@@ -1,16 +1,28 @@
 use std::time::Duration;
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use rand::rng;
-use rlwe_encryption::Message;
+use rand::{rng, Rng};
+use rlwe_encryption::{DecryptKey, IntField, Message, Polynomial, StandardZq, ZqI64};
 
 criterion_group! {
     name = standard;
     config = Criterion::default().sample_size(10).measurement_time(Duration::from_micros(600));
-    targets = bench_standard_encrypt, bench_standard_decrypt
+    targets = bench_standard_encrypt, bench_standard_encrypt_slice, bench_standard_encrypt_short_messages,
+        bench_standard_decrypt, bench_modulo_coefficients, bench_mul_scalar,
+        bench_i64_encrypt_ntt, bench_sample_uniform, bench_sample_gaussian, bench_sample_cbd
 }
 
+#[cfg(feature = "rayon")]
+criterion_group! {
+    name = parallel;
+    config = Criterion::default().sample_size(10);
+    targets = bench_encrypt_batch_par
+}
+
+#[cfg(not(feature = "rayon"))]
 criterion_main!(standard);
+#[cfg(feature = "rayon")]
+criterion_main!(standard, parallel);
 
 fn bench_standard_encrypt(c: &mut Criterion) {
     let rng = &mut rng();
@@ -28,6 +40,47 @@ fn bench_standard_encrypt(c: &mut Criterion) {
     });
 }
 
+// Compared with `standard_encrypt`, the message is borrowed instead of cloned for each
+// iteration, which saves one allocation per encryption.
+fn bench_standard_encrypt_slice(c: &mut Criterion) {
+    let rng = &mut rng();
+    let (ek, _) = rlwe_encryption::standard(rng);
+    let buffer = Message::<StandardZq, 256>::random(rng, 256).data();
+
+    c.bench_function("standard_encrypt_slice", |b| {
+        b.iter(|| {
+            let _ = ek.encrypt_slice(rng, &buffer);
+        })
+    });
+}
+
+// Compares encrypting 100 short messages of 16 bits each from owned copies against borrowed
+// slices of one buffer. The owned messages cost one more small allocation per message, which
+// is minor compared with the two polynomial multiplications of each encryption.
+fn bench_standard_encrypt_short_messages(c: &mut Criterion) {
+    let rng = &mut rng();
+    let (ek, _) = rlwe_encryption::standard(rng);
+    let buffer = Message::<StandardZq, 256>::random(rng, 256)
+        .data()
+        .repeat(7);
+
+    c.bench_function("standard_encrypt_short_messages_owned", |b| {
+        b.iter(|| {
+            for chunk in buffer.chunks(16).take(100) {
+                let m = Message::new(chunk.to_vec());
+                let _ = ek.encrypt(rng, m);
+            }
+        })
+    });
+    c.bench_function("standard_encrypt_short_messages_slice", |b| {
+        b.iter(|| {
+            for chunk in buffer.chunks(16).take(100) {
+                let _ = ek.encrypt_slice(rng, chunk);
+            }
+        })
+    });
+}
+
 fn bench_standard_decrypt(c: &mut Criterion) {
     let rng = &mut rng();
     let (ek, dk) = rlwe_encryption::standard(rng);
@@ -44,3 +97,203 @@ fn bench_standard_decrypt(c: &mut Criterion) {
         )
     });
 }
+
+fn bench_modulo_coefficients(c: &mut Criterion) {
+    let rng = &mut rng();
+    // magnitude of the coefficients after multiplying a uniform and a small polynomial
+    let coeffs: Vec<i32> = (0..256)
+        .map(|_| rng.random_range(-256 * 1664..=256 * 1664))
+        .collect();
+
+    c.bench_function("modulo_coefficients_barrett", |b| {
+        b.iter_batched(
+            || coeffs.clone(),
+            |mut coeffs| {
+                coeffs.iter_mut().for_each(|c| *c = StandardZq::modulo(c));
+                coeffs
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("modulo_coefficients_rem_euclid", |b| {
+        b.iter_batched(
+            || coeffs.clone(),
+            |mut coeffs| {
+                coeffs.iter_mut().for_each(|c| {
+                    let a = c.rem_euclid(StandardZq::Q);
+                    *c = if a > StandardZq::Q / 2 {
+                        a - StandardZq::Q
+                    } else {
+                        a
+                    };
+                });
+                coeffs
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // vectorized, also used by `standard_encrypt` with the `simd` feature
+    #[cfg(feature = "simd")]
+    c.bench_function("modulo_coefficients_simd", |b| {
+        b.iter_batched(
+            || coeffs.clone(),
+            |mut coeffs| {
+                StandardZq::modulo_slice(&mut coeffs);
+                coeffs
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_mul_scalar(c: &mut Criterion) {
+    let rng = &mut rng();
+    let (ek, _) = rlwe_encryption::standard(rng);
+    let message = Message::random(rng, 256);
+    let ciphertext = ek.encrypt(rng, message);
+
+    c.bench_function("mul_scalar", |b| {
+        let mut ciphertext = ciphertext.clone();
+        b.iter(|| {
+            for _ in 0..10 {
+                ciphertext = ciphertext.mul_scalar(&3);
+            }
+        })
+    });
+
+    c.bench_function("mul_scalar_assign", |b| {
+        let mut ciphertext = ciphertext.clone();
+        b.iter(|| {
+            for _ in 0..10 {
+                ciphertext.mul_scalar_assign(&3);
+            }
+        })
+    });
+}
+
+/// The same field as [ZqI64], but with the schoolbook polynomial multiplication.
+#[derive(Clone, Debug)]
+struct ZqI64Schoolbook;
+
+impl IntField for ZqI64Schoolbook {
+    type I = i64;
+    const Q: i64 = ZqI64::Q;
+    const B: i64 = ZqI64::B;
+
+    fn modulo(x: &Self::I) -> Self::I {
+        ZqI64::modulo(x)
+    }
+
+    fn to_word(x: &Self::I) -> Option<i64> {
+        ZqI64::to_word(x)
+    }
+
+    fn from_word(x: i64) -> Option<Self::I> {
+        ZqI64::from_word(x)
+    }
+}
+
+// Compares the encryption with N = 1024 using the NTT (`ZqI64`) against the schoolbook
+// multiplication. Expect the NTT to be several times faster, as the two multiplications
+// `a * r` and `t * r` dominate the encryption.
+fn bench_i64_encrypt_ntt(c: &mut Criterion) {
+    let rng = &mut rng();
+    let buffer = Message::<ZqI64, 1024>::random(rng, 1024).data();
+
+    let (ek, _) = rlwe_encryption::standard_i64(rng);
+    c.bench_function("i64_encrypt_ntt", |b| {
+        b.iter(|| {
+            let _ = ek.encrypt_slice(rng, &buffer);
+        })
+    });
+
+    let (ek, _) = rlwe_encryption::key_gen::<ZqI64Schoolbook, 1024>(rng);
+    c.bench_function("i64_encrypt_schoolbook", |b| {
+        b.iter(|| {
+            let _ = ek.encrypt_slice(rng, &buffer);
+        })
+    });
+}
+
+// Compares encrypting 10k short messages on the rayon thread pool against a sequential loop.
+// Expect a speedup close to the number of cores, as the messages are independent.
+#[cfg(feature = "rayon")]
+fn bench_encrypt_batch_par(c: &mut Criterion) {
+    let rng = &mut rng();
+    let (ek, _) = rlwe_encryption::standard(rng);
+    let messages = (0..10_000)
+        .map(|_| Message::<StandardZq, 256>::random(rng, 16).data())
+        .collect::<Vec<_>>();
+
+    c.bench_function("encrypt_batch_par", |b| {
+        b.iter(|| ek.encrypt_batch_par(&messages, [0u8; 32]))
+    });
+
+    c.bench_function("encrypt_batch_sequential", |b| {
+        b.iter(|| {
+            messages
+                .iter()
+                .map(|m| ek.encrypt_slice(rng, m))
+                .collect::<Vec<_>>()
+        })
+    });
+}
+
+// The noise sampling benches compare the cost of sampling a length-N small polynomial from
+// the distributions with similar variance. Only the uniform distribution in [-B, B] is used
+// by the library, the others are sampled here for comparison. Expect the CBD to be the
+// cheapest (bit counting on a few random words), the uniform one close to it (one bounded
+// random integer per coefficient), and the Gaussian one the slowest (floating point
+// logarithm, square root and cosine per coefficient), besides being hard to sample in
+// constant time.
+
+fn bench_sample_uniform(c: &mut Criterion) {
+    let rng = &mut rng();
+
+    c.bench_function("sample_uniform", |b| {
+        b.iter(|| DecryptKey::<StandardZq, 256>::random_secret(rng))
+    });
+}
+
+fn bench_sample_gaussian(c: &mut Criterion) {
+    let rng = &mut rng();
+    // the variance of the uniform distribution in [-1, 1]
+    let sigma = (2.0f64 / 3.0).sqrt();
+
+    c.bench_function("sample_gaussian", |b| {
+        b.iter(|| {
+            // Box-Muller transform, rounded to the nearest integer
+            let coeffs = (0..256)
+                .map(|_| {
+                    let u1: f64 = 1.0 - rng.random::<f64>();
+                    let u2: f64 = rng.random();
+                    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                    (z * sigma).round() as i32
+                })
+                .collect();
+            Polynomial::<i32, 256>::new(coeffs)
+        })
+    });
+}
+
+fn bench_sample_cbd(c: &mut Criterion) {
+    let rng = &mut rng();
+
+    c.bench_function("sample_cbd", |b| {
+        b.iter(|| {
+            // centered binomial distribution with eta = 1, i.e. a - b for random bits a, b,
+            // taking 32 coefficients from each random u64
+            let coeffs = (0..256 / 32)
+                .flat_map(|_| {
+                    let bits = rng.random::<u64>();
+                    (0..32).map(move |i| {
+                        ((bits >> (2 * i)) & 1) as i32 - ((bits >> (2 * i + 1)) & 1) as i32
+                    })
+                })
+                .collect();
+            Polynomial::<i32, 256>::new(coeffs)
+        })
+    });
+}
@@ -0,0 +1,73 @@
+//! Measures the encryption and decryption throughput in messages per second with the
+//! standard parameters, for capacity planning.
+//!
+//! Run with `cargo run --release --example throughput [seconds]`, the time window of each
+//! measurement defaults to 3 seconds.
+
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rlwe_encryption::{CipherText, DecryptKey, EncryptKey, Message, StandardZq};
+
+fn main() {
+    let seconds = std::env::args()
+        .nth(1)
+        .map(|arg| {
+            arg.parse()
+                .expect("the time window must be a number of seconds")
+        })
+        .unwrap_or(3.0);
+    let duration = Duration::from_secs_f64(seconds);
+
+    let rng = &mut rand::rng();
+    let (ek, dk) = rlwe_encryption::standard(rng);
+
+    let encrypt = measure_throughput(&ek, rng, duration);
+    println!("encryption: {encrypt:.1} messages/s");
+
+    let decrypt = measure_decrypt_throughput(&ek, &dk, rng, duration);
+    println!("decryption: {decrypt:.1} messages/s");
+}
+
+/// Returns the number of random messages of `N` bits encrypted per second over the time
+/// window `duration`.
+fn measure_throughput(
+    ek: &EncryptKey<StandardZq, 256>,
+    rng: &mut impl Rng,
+    duration: Duration,
+) -> f64 {
+    let messages = (0..64)
+        .map(|_| Message::random(rng, 256))
+        .collect::<Vec<_>>();
+
+    let mut count = 0usize;
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        let _ = ek.encrypt(rng, messages[count % messages.len()].clone());
+        count += 1;
+    }
+    count as f64 / start.elapsed().as_secs_f64()
+}
+
+/// Returns the number of ciphertexts decrypted per second over the time window `duration`.
+fn measure_decrypt_throughput(
+    ek: &EncryptKey<StandardZq, 256>,
+    dk: &DecryptKey<StandardZq, 256>,
+    rng: &mut impl Rng,
+    duration: Duration,
+) -> f64 {
+    let ciphertexts: Vec<CipherText<StandardZq, 256>> = (0..64)
+        .map(|_| {
+            let m = Message::random(rng, 256);
+            ek.encrypt(rng, m)
+        })
+        .collect();
+
+    let mut count = 0usize;
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        let _ = dk.decrypt(ciphertexts[count % ciphertexts.len()].clone());
+        count += 1;
+    }
+    count as f64 / start.elapsed().as_secs_f64()
+}
@@ -0,0 +1,61 @@
+//! Conversions between polynomials and [ndarray] arrays for numerical analysis.
+
+use ndarray::Array1;
+use poly_ring_xnp1::Polynomial;
+
+use crate::{
+    intfield::IntField, polynomial::to_fixed_coeffs_vec, CipherText, DecryptKey, EncryptKey, Error,
+};
+
+/// Converts the polynomial into an array of its `N` coefficients.
+pub fn to_ndarray<Zq: IntField, const N: usize>(poly: &Polynomial<Zq::I, N>) -> Array1<Zq::I> {
+    Array1::from(to_fixed_coeffs_vec::<Zq, N>(poly))
+}
+
+/// Converts the array of coefficients into a polynomial, reducing each coefficient with
+/// [IntField::modulo]. Returns [Error::InvalidLength] if the array has more than `N` elements.
+pub fn from_ndarray<Zq: IntField, const N: usize>(
+    arr: &Array1<Zq::I>,
+) -> Result<Polynomial<Zq::I, N>, Error> {
+    if arr.len() > N {
+        return Err(Error::InvalidLength);
+    }
+    Ok(Polynomial::new(arr.iter().map(Zq::modulo).collect()))
+}
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Returns the polynomials `[a, t]` of the key as arrays.
+    pub fn to_ndarrays(&self) -> [Array1<Zq::I>; 2] {
+        [to_ndarray::<Zq, N>(&self.a), to_ndarray::<Zq, N>(&self.t)]
+    }
+
+    /// Creates the key from the arrays of the polynomials `a` and `t`. See [from_ndarray].
+    pub fn from_ndarrays(a: &Array1<Zq::I>, t: &Array1<Zq::I>) -> Result<Self, Error> {
+        Ok(Self {
+            a: from_ndarray::<Zq, N>(a)?,
+            t: from_ndarray::<Zq, N>(t)?,
+        })
+    }
+}
+
+impl<Zq: IntField, const N: usize> DecryptKey<Zq, N> {
+    /// Returns the secret polynomial `s` of the key as an array.
+    pub fn to_ndarray(&self) -> Array1<Zq::I> {
+        to_ndarray::<Zq, N>(&self.s)
+    }
+}
+
+impl<Zq: IntField, const N: usize> CipherText<Zq, N> {
+    /// Returns the polynomials `[u, v]` of the ciphertext as arrays.
+    pub fn to_ndarrays(&self) -> [Array1<Zq::I>; 2] {
+        [to_ndarray::<Zq, N>(&self.u), to_ndarray::<Zq, N>(&self.v)]
+    }
+
+    /// Creates the ciphertext from the arrays of the polynomials `u` and `v`. See [from_ndarray].
+    pub fn from_ndarrays(u: &Array1<Zq::I>, v: &Array1<Zq::I>) -> Result<Self, Error> {
+        Ok(Self {
+            u: from_ndarray::<Zq, N>(u)?,
+            v: from_ndarray::<Zq, N>(v)?,
+        })
+    }
+}
@@ -0,0 +1,407 @@
+//! Defines the arbitrary precision integer [BigI] and the field [ZqBig] with a modulus which
+//! does not fit in the primitive integer types.
+
+use alloc::borrow::Cow;
+use core::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+use num::{
+    bigint::{BigInt, BigUint, ParseBigIntError, Sign},
+    FromPrimitive, Integer, Num, One, Signed, ToPrimitive, Zero,
+};
+use rand::{
+    distr::uniform::{Error as UniformError, SampleBorrow, SampleUniform, UniformSampler},
+    Rng,
+};
+
+use crate::IntField;
+
+/// An arbitrary precision integer to be used as the integer type `I` of an [IntField], e.g.
+/// [ZqBig]. It wraps [BigInt] with the traits required by [IntField], including the
+/// uniform sampling of `rand` and a `const` constructor for the constants `Q` and `B`.
+///
+/// The arithmetic allocates for each operation, so it is orders of magnitude slower than
+/// the primitive integer types, see [ZqBig].
+#[derive(Clone)]
+pub struct BigI(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    /// A non-negative integer given by its little-endian `u32` digits in a `const`.
+    Static(&'static [u32]),
+    Dynamic(BigInt),
+}
+
+impl BigI {
+    /// Creates the non-negative integer with the little-endian `u32` digits. It is a `const`
+    /// function to define the constants of an [IntField].
+    pub const fn from_static_digits(digits: &'static [u32]) -> Self {
+        Self(Repr::Static(digits))
+    }
+
+    /// Returns the value as a [BigInt].
+    pub fn into_bigint(self) -> BigInt {
+        self.value().into_owned()
+    }
+
+    fn value(&self) -> Cow<'_, BigInt> {
+        match &self.0 {
+            Repr::Static(digits) => Cow::Owned(BigInt::from_biguint(
+                Sign::Plus,
+                BigUint::from_slice(digits),
+            )),
+            Repr::Dynamic(x) => Cow::Borrowed(x),
+        }
+    }
+}
+
+impl From<BigInt> for BigI {
+    fn from(x: BigInt) -> Self {
+        Self(Repr::Dynamic(x))
+    }
+}
+
+impl fmt::Debug for BigI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.value(), f)
+    }
+}
+
+impl fmt::Display for BigI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.value(), f)
+    }
+}
+
+impl PartialEq for BigI {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for BigI {}
+
+impl PartialOrd for BigI {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigI {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($($op:ident, $method:ident;)*) => {
+        $(
+            impl $op for BigI {
+                type Output = BigI;
+
+                fn $method(self, rhs: BigI) -> BigI {
+                    (&self).$method(&rhs)
+                }
+            }
+
+            impl<'a> $op<&'a BigI> for &'a BigI {
+                type Output = BigI;
+
+                fn $method(self, rhs: &'a BigI) -> BigI {
+                    BigI::from((&*self.value()).$method(&*rhs.value()))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_op! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+    Rem, rem;
+}
+
+impl Neg for BigI {
+    type Output = BigI;
+
+    fn neg(self) -> BigI {
+        -&self
+    }
+}
+
+impl Neg for &BigI {
+    type Output = BigI;
+
+    fn neg(self) -> BigI {
+        BigI::from(-&*self.value())
+    }
+}
+
+impl Zero for BigI {
+    fn zero() -> Self {
+        Self::from(BigInt::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value().is_zero()
+    }
+}
+
+impl One for BigI {
+    fn one() -> Self {
+        Self::from(BigInt::one())
+    }
+}
+
+impl Num for BigI {
+    type FromStrRadixErr = ParseBigIntError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        BigInt::from_str_radix(s, radix).map(Self::from)
+    }
+}
+
+impl Integer for BigI {
+    fn div_floor(&self, other: &Self) -> Self {
+        Self::from(self.value().div_floor(&other.value()))
+    }
+
+    fn mod_floor(&self, other: &Self) -> Self {
+        Self::from(self.value().mod_floor(&other.value()))
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        Self::from(self.value().gcd(&other.value()))
+    }
+
+    fn lcm(&self, other: &Self) -> Self {
+        Self::from(self.value().lcm(&other.value()))
+    }
+
+    fn is_multiple_of(&self, other: &Self) -> bool {
+        Integer::is_multiple_of(&*self.value(), &other.value())
+    }
+
+    fn is_even(&self) -> bool {
+        self.value().is_even()
+    }
+
+    fn is_odd(&self) -> bool {
+        self.value().is_odd()
+    }
+
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (d, r) = self.value().div_rem(&other.value());
+        (Self::from(d), Self::from(r))
+    }
+}
+
+impl Signed for BigI {
+    fn abs(&self) -> Self {
+        Self::from(self.value().abs())
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        Self::from(self.value().abs_sub(&other.value()))
+    }
+
+    fn signum(&self) -> Self {
+        Self::from(self.value().signum())
+    }
+
+    fn is_positive(&self) -> bool {
+        self.value().is_positive()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.value().is_negative()
+    }
+}
+
+impl ToPrimitive for BigI {
+    fn to_i64(&self) -> Option<i64> {
+        self.value().to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.value().to_u64()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.value().to_i128()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        self.value().to_u128()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.value().to_f64()
+    }
+}
+
+impl FromPrimitive for BigI {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(Self::from(BigInt::from(n)))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Self::from(BigInt::from(n)))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        Some(Self::from(BigInt::from(n)))
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(Self::from(BigInt::from(n)))
+    }
+}
+
+/// Wipes the digits of the value and resets it to zero. [BigInt] does not expose its digits
+/// mutably, so the bits are cleared one by one from the lowest, which overwrites the digits
+/// in place before the top one is cleared and the buffer is released.
+///
+/// Only the value itself is wiped, not the temporaries allocated by the arithmetic.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for BigI {
+    fn zeroize(&mut self) {
+        if let Repr::Dynamic(x) = &mut self.0 {
+            let (_, mut magnitude) = core::mem::take(x).into_parts();
+            for bit in 0..magnitude.bits() {
+                magnitude.set_bit(bit, false);
+            }
+        }
+        *self = Self::zero();
+    }
+}
+
+/// Serializes the value as a decimal string.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BigI {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BigI {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <alloc::string::String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::from_str_radix(&s, 10).map_err(serde::de::Error::custom)
+    }
+}
+
+impl SampleUniform for BigI {
+    type Sampler = UniformBigI;
+}
+
+/// Samples [BigI] uniformly in a range, by rejection sampling of random bits.
+#[derive(Clone, Debug)]
+pub struct UniformBigI {
+    low: BigInt,
+    /// The number of values in the range, i.e. `high - low + 1` for an inclusive range.
+    size: BigUint,
+}
+
+impl UniformSampler for UniformBigI {
+    type X = BigI;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Result<Self, UniformError>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (low.borrow().value(), high.borrow().value());
+        let size = (&*high - &*low).to_biguint().filter(|size| !size.is_zero());
+        Ok(Self {
+            low: low.into_owned(),
+            size: size.ok_or(UniformError::EmptyRange)?,
+        })
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Result<Self, UniformError>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (low.borrow().value(), high.borrow().value());
+        let size = (&*high - &*low + BigInt::one())
+            .to_biguint()
+            .filter(|size| !size.is_zero());
+        Ok(Self {
+            low: low.into_owned(),
+            size: size.ok_or(UniformError::EmptyRange)?,
+        })
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        let bits = (&self.size - 1u32).bits();
+        let mut bytes = alloc::vec![0u8; bits.div_ceil(8) as usize];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            if !bits.is_multiple_of(8) {
+                // keep only the lowest `bits` bits, so that at most half of the samples
+                // are rejected
+                *bytes.last_mut().unwrap() &= (1u8 << (bits % 8)) - 1;
+            }
+            let x = BigUint::from_bytes_le(&bytes);
+            if x < self.size {
+                return BigI::from(&self.low + BigInt::from(x));
+            }
+        }
+    }
+}
+
+/// The little-endian `u32` digits of the Mersenne prime `2^521 - 1`.
+const M521: [u32; 17] = {
+    let mut digits = [u32::MAX; 17];
+    digits[16] = (1 << 9) - 1;
+    digits
+};
+
+/// A field with the Mersenne prime `Q = 2^521 - 1` and `B = 1`, whose coefficients are
+/// [BigI]. It is intended for experiments with moduli which overflow the primitive integer
+/// types.
+///
+/// Every coefficient operation allocates, and the polynomials are multiplied with the
+/// schoolbook algorithm on 521-bit integers, so the encryption and decryption are a few
+/// hundred times slower than with [StandardZq](crate::StandardZq) for the same `N`, e.g.
+/// about 18ms instead of 60us for an encryption and decryption with `N = 256` in release
+/// mode.
+///
+/// The functions which convert the coefficients to primitive integers, e.g. `to_bytes` and
+/// the NTT, panic for the coefficients which do not fit. The constant-time rounding in
+/// decryption falls back to the branching comparison.
+///
+/// ```
+/// use rlwe_encryption::{key_gen, Message, ZqBig};
+///
+/// let rng = &mut rand::rng();
+/// let (ek, dk) = key_gen::<ZqBig, 16>(rng);
+/// let m = Message::<ZqBig, 16>::random(rng, 16);
+/// let c = ek.encrypt(rng, m.clone());
+/// assert_eq!(dk.decrypt(c), m.data());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZqBig;
+
+impl IntField for ZqBig {
+    type I = BigI;
+    const Q: BigI = BigI::from_static_digits(&M521);
+    const B: BigI = BigI::from_static_digits(&[1]);
+
+    fn modulo(x: &Self::I) -> Self::I {
+        let q = Self::Q.value().into_owned();
+        let a = x.value().mod_floor(&q);
+        if a > &q >> 1 {
+            BigI::from(a - q)
+        } else {
+            BigI::from(a)
+        }
+    }
+}
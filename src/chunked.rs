@@ -0,0 +1,318 @@
+//! Encryption of long messages in chunks, either as bits tagged with their indices for
+//! reassembly, or as bytes, possibly streamed piece by piece.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use num::{One, Zero};
+
+use rand::Rng;
+
+use crate::{CipherText, DecryptKey, EncryptKey, Error, IntField, Message};
+
+/// A ciphertext of a chunk of a long message, tagged with the index of the chunk and the
+/// total number of chunks, so that the message can be reassembled even if the chunks
+/// arrive out of order or are retransmitted.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct IndexedCipherText<Zq: IntField, const N: usize> {
+    /// The index of the chunk, starting from 0.
+    pub index: usize,
+    /// The total number of chunks of the message.
+    pub total: usize,
+    /// The ciphertext of the chunk.
+    pub ciphertext: CipherText<Zq, N>,
+}
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Splits the binary message `m` of any length into chunks of `N` bits, and encrypts
+    /// each chunk tagged with its index (see [IndexedCipherText]). Returns
+    /// [Error::InvalidMessage] if the message is not binary.
+    pub fn encrypt_chunked(
+        &self,
+        rng: &mut impl Rng,
+        m: &[Zq::I],
+    ) -> Result<Vec<IndexedCipherText<Zq, N>>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let chunks = m
+            .chunks(N)
+            .map(|chunk| Message::try_new(chunk.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total = chunks.len();
+        Ok(chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| IndexedCipherText {
+                index,
+                total,
+                ciphertext: self.encrypt(rng, chunk),
+            })
+            .collect())
+    }
+
+    /// Encrypts a byte payload. The input is split into chunks of `N/8` bytes, and each
+    /// byte is expanded LSB-first into 8 binary coefficients, so that each chunk fills
+    /// one ciphertext of `N` bits. `N` must be a multiple of 8.
+    ///
+    /// If the length of `bytes` is not a multiple of `N/8`, the last chunk is padded with
+    /// zero bits up to `N` bits, so every ciphertext has the same size. The padding is
+    /// removed by [DecryptKey::decrypt_bytes] given the original length.
+    ///
+    /// It panics if `N` is not a positive multiple of 8. Use [EncryptKey::try_encrypt_bytes]
+    /// to get an error instead.
+    pub fn encrypt_bytes(&self, rng: &mut impl Rng, bytes: &[u8]) -> Vec<CipherText<Zq, N>>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.try_encrypt_bytes(rng, bytes)
+            .expect("N must be a positive multiple of 8")
+    }
+
+    /// Encrypts a byte payload like [EncryptKey::encrypt_bytes]. Returns
+    /// [Error::InvalidParams] if `N` is not a positive multiple of 8.
+    pub fn try_encrypt_bytes(
+        &self,
+        rng: &mut impl Rng,
+        bytes: &[u8],
+    ) -> Result<Vec<CipherText<Zq, N>>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        if N < 8 || !N.is_multiple_of(8) {
+            return Err(Error::InvalidParams);
+        }
+        Ok(bytes
+            .chunks(N / 8)
+            .map(|chunk| self.encrypt_byte_chunk(rng, chunk))
+            .collect())
+    }
+
+    /// Creates a [StreamEncryptor] to encrypt a byte payload pushed piece by piece, in the
+    /// same format as [EncryptKey::encrypt_bytes]. It panics if `N` is not a positive
+    /// multiple of 8, since a chunk of `N/8` bytes would be empty.
+    pub fn stream_encryptor<'a, R: Rng>(&'a self, rng: &'a mut R) -> StreamEncryptor<'a, Zq, N, R> {
+        assert!(
+            N >= 8 && N.is_multiple_of(8),
+            "N={N} is not a positive multiple of 8"
+        );
+        StreamEncryptor {
+            ek: self,
+            rng,
+            buffer: Vec::with_capacity(N / 8),
+            ciphertexts: Vec::new(),
+        }
+    }
+
+    /// Encrypts a chunk of at most `N/8` bytes, expanded LSB-first and padded with zero
+    /// bits up to `N` bits.
+    fn encrypt_byte_chunk(&self, rng: &mut impl Rng, chunk: &[u8]) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let mut data = chunk
+            .iter()
+            .flat_map(|byte| {
+                (0..8).map(move |i| {
+                    if (byte >> i) & 1 == 1 {
+                        Zq::I::one()
+                    } else {
+                        Zq::I::zero()
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        data.resize(N, Zq::I::zero());
+        self.encrypt(rng, Message { data })
+    }
+}
+
+impl<Zq: IntField, const N: usize> DecryptKey<Zq, N> {
+    /// Decrypts the chunks produced by [EncryptKey::encrypt_chunked] in any order, and
+    /// reassembles the message of `total_len` bits. Retransmitted chunks (with the same
+    /// index) are decrypted once. Returns [Error::IncompleteChunks] if a chunk is missing
+    /// or the chunks disagree on the total, and [Error::InvalidLength] if `total_len` does
+    /// not fit in the chunks. An empty message has no chunks, with `total_len` 0.
+    pub fn decrypt_chunked(
+        &self,
+        chunks: Vec<IndexedCipherText<Zq, N>>,
+        total_len: usize,
+    ) -> Result<Vec<Zq::I>, Error>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let mut chunks = chunks;
+        chunks.sort_by_key(|chunk| chunk.index);
+        chunks.dedup_by_key(|chunk| chunk.index);
+
+        let total = chunks.first().map_or(0, |chunk| chunk.total);
+        let complete = chunks.len() == total
+            && chunks
+                .iter()
+                .enumerate()
+                .all(|(i, chunk)| chunk.index == i && chunk.total == total);
+        if !complete {
+            return Err(Error::IncompleteChunks);
+        }
+        // every chunk but the last is full, and an empty message has no chunks
+        if total_len > total * N || (total > 0 && total_len <= (total - 1) * N) {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut m = chunks
+            .into_iter()
+            .flat_map(|chunk| self.decrypt(chunk.ciphertext))
+            .collect::<Vec<_>>();
+        m.truncate(total_len);
+        Ok(m)
+    }
+
+    /// Decrypts the ciphertexts produced by [EncryptKey::encrypt_bytes], packs the bits
+    /// back into bytes LSB-first, and truncates the result to `original_len` bytes to
+    /// remove the padding of the last chunk.
+    pub fn decrypt_bytes(&self, cs: &[CipherText<Zq, N>], original_len: usize) -> Vec<u8>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let mut bytes = cs
+            .iter()
+            .flat_map(|c| self.decrypt_byte_chunk(c.clone()))
+            .collect::<Vec<_>>();
+        bytes.truncate(original_len);
+        bytes
+    }
+
+    /// Creates a [StreamDecryptor] to decrypt the ciphertexts of a [StreamEncryptor] (or
+    /// [EncryptKey::encrypt_bytes]) pushed one by one.
+    pub fn stream_decryptor(&self) -> StreamDecryptor<'_, Zq, N> {
+        StreamDecryptor {
+            dk: self,
+            bytes: Vec::new(),
+        }
+    }
+
+    /// Decrypts a ciphertext of [EncryptKey::encrypt_byte_chunk] into `N/8` bytes, packing
+    /// the bits LSB-first.
+    fn decrypt_byte_chunk(&self, c: CipherText<Zq, N>) -> Vec<u8>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let mut bits = self.decrypt(c);
+        bits.resize(N, Zq::I::zero());
+        bits.chunks(8)
+            .map(|bits| {
+                bits.iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, bit)| acc | ((bit.is_one() as u8) << i))
+            })
+            .collect()
+    }
+}
+
+/// Encrypts a byte payload pushed piece by piece, created by [EncryptKey::stream_encryptor].
+///
+/// The input is buffered until a chunk of `N/8` bytes accumulates, which is then encrypted
+/// into a ciphertext, in the same format as [EncryptKey::encrypt_bytes]. The memory is
+/// bounded by one chunk plus the ciphertexts not yet taken with
+/// [StreamEncryptor::take_ciphertexts], regardless of the size of the payload.
+///
+/// ```
+/// let rng = &mut rand::rng();
+/// let (ek, dk) = rlwe_encryption::standard(rng);
+///
+/// let mut cs = Vec::new();
+/// let mut encryptor = ek.stream_encryptor(rng);
+/// for piece in [&b"hello "[..], b"streaming ", b"world"] {
+///     encryptor.push(piece);
+///     cs.extend(encryptor.take_ciphertexts());
+/// }
+/// cs.extend(encryptor.finish());
+///
+/// let mut decryptor = dk.stream_decryptor();
+/// cs.into_iter().for_each(|c| decryptor.push(c));
+/// let mut bytes = decryptor.finish();
+/// bytes.truncate(21);
+/// assert_eq!(bytes, b"hello streaming world");
+/// ```
+pub struct StreamEncryptor<'a, Zq: IntField, const N: usize, R: Rng> {
+    ek: &'a EncryptKey<Zq, N>,
+    rng: &'a mut R,
+    buffer: Vec<u8>,
+    ciphertexts: Vec<CipherText<Zq, N>>,
+}
+
+impl<Zq: IntField, const N: usize, R: Rng> StreamEncryptor<'_, Zq, N, R>
+where
+    for<'b> &'b Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    /// Appends the bytes to the payload, and encrypts each chunk of `N/8` bytes that
+    /// becomes full.
+    pub fn push(&mut self, bytes: &[u8]) {
+        let mut bytes = bytes;
+        while !bytes.is_empty() {
+            let len = (N / 8 - self.buffer.len()).min(bytes.len());
+            self.buffer.extend_from_slice(&bytes[..len]);
+            bytes = &bytes[len..];
+            if self.buffer.len() == N / 8 {
+                let c = self.ek.encrypt_byte_chunk(self.rng, &self.buffer);
+                self.ciphertexts.push(c);
+                self.buffer.clear();
+            }
+        }
+    }
+
+    /// Returns the ciphertexts of the full chunks encrypted so far, and removes them from
+    /// the encryptor.
+    pub fn take_ciphertexts(&mut self) -> Vec<CipherText<Zq, N>> {
+        core::mem::take(&mut self.ciphertexts)
+    }
+
+    /// Encrypts the remaining bytes padded with zero bits, and returns the ciphertexts not
+    /// yet taken. The padding is removed by truncating the decrypted payload to its
+    /// original length.
+    pub fn finish(mut self) -> Vec<CipherText<Zq, N>> {
+        if !self.buffer.is_empty() {
+            let c = self.ek.encrypt_byte_chunk(self.rng, &self.buffer);
+            self.ciphertexts.push(c);
+        }
+        self.ciphertexts
+    }
+}
+
+/// Decrypts the ciphertexts of a [StreamEncryptor] pushed one by one, created by
+/// [DecryptKey::stream_decryptor]. Each ciphertext is decrypted into `N/8` bytes.
+pub struct StreamDecryptor<'a, Zq: IntField, const N: usize> {
+    dk: &'a DecryptKey<Zq, N>,
+    bytes: Vec<u8>,
+}
+
+impl<Zq: IntField, const N: usize> StreamDecryptor<'_, Zq, N>
+where
+    for<'b> &'b Zq::I:
+        Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+{
+    /// Decrypts the next ciphertext of the stream.
+    pub fn push(&mut self, c: CipherText<Zq, N>) {
+        let bytes = self.dk.decrypt_byte_chunk(c);
+        self.bytes.extend(bytes);
+    }
+
+    /// Returns the bytes decrypted so far, and removes them from the decryptor.
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.bytes)
+    }
+
+    /// Returns the bytes not yet taken. The last chunk includes the zero padding, which is
+    /// removed by truncating the payload to its original length.
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
@@ -1,8 +1,26 @@
 //! Defines the CipherText struct.
 
+use alloc::{vec, vec::Vec};
+use core::{
+    iter::Sum,
+    ops::{Add, Mul, Neg, Sub},
+};
+use hmac::{Hmac, Mac};
+use num::{FromPrimitive, Integer, One, ToPrimitive, Zero};
 use poly_ring_xnp1::Polynomial;
+use rand::{Rng, RngCore};
+use sha3::Sha3_256;
 
-use crate::intfield::IntField;
+use crate::{
+    intfield::IntField,
+    polynomial::{
+        coefficient_bit_width, from_fixed_width_bytes, modulo_coefficients, pack_coefficients,
+        packed_size, scale_coefficients, to_fixed_coeffs_vec, to_fixed_width_bytes,
+        unpack_coefficients,
+    },
+    xof::XofRng,
+    DecodeError, Error, Message,
+};
 
 /// CipherText created by the encryption method.
 ///
@@ -10,9 +28,775 @@ use crate::intfield::IntField;
 /// where `I` is the integer type of the field `Zq`. Depending on the
 /// serialization method, the size can be slightly larger (for additional
 /// metadata).
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// ## Serialization formats
+///
+/// With the `serde` feature, the ciphertext can be serialized with the derived
+/// serde implementation, which is convenient to embed in other serde data
+/// structures and is the format used by existing persisted data.
+///
+/// Alternatively, [CipherText::to_compact] produces a bit-packed encoding which
+/// stores each coefficient with only `ceil(log2(Q))` bits, i.e. `2 * N * ceil(log2(Q)) / 8`
+/// bytes in total. Prefer the compact format for storage or transmission where size
+/// matters. Both formats carry the same information, so a ciphertext can be converted
+/// from one to another without data loss.
+///
+/// For ciphertexts with mostly zero coefficients, [CipherText::to_sparse] stores only
+/// the nonzero coefficients with their indices, falling back to the compact format when
+/// it is not smaller.
+///
+/// [CipherText::to_bytes] produces a simple fixed-width format which is easy to implement
+/// in other languages, at the cost of `2 * N * sizeof(I)` bytes.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "CipherTextParts<Zq::I>")
+)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct CipherText<Zq: IntField, const N: usize> {
     pub(crate) u: Polynomial<Zq::I, N>,
     pub(crate) v: Polynomial<Zq::I, N>,
 }
+
+/// The serde format of [CipherText], deserialized first so that polynomials with more than
+/// `N` coefficients are rejected with an error by [CipherText::from_parts], instead of
+/// panicking in [Polynomial].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "CipherText")]
+struct CipherTextParts<I> {
+    u: Vec<I>,
+    v: Vec<I>,
+}
+
+#[cfg(feature = "serde")]
+impl<Zq: IntField, const N: usize> TryFrom<CipherTextParts<Zq::I>> for CipherText<Zq, N> {
+    type Error = Error;
+
+    fn try_from(parts: CipherTextParts<Zq::I>) -> Result<Self, Error> {
+        Self::from_parts(parts.u, parts.v)
+    }
+}
+
+/// Clones the polynomials. Unlike the derived [Clone], it does not require `Zq: Clone`.
+impl<Zq: IntField, const N: usize> Clone for CipherText<Zq, N> {
+    fn clone(&self) -> Self {
+        Self {
+            u: self.u.clone(),
+            v: self.v.clone(),
+        }
+    }
+}
+
+impl<Zq: IntField, const N: usize> CipherText<Zq, N> {
+    /// Creates a ciphertext from the coefficients of its polynomials `u` and `v`. Returns
+    /// [Error::InvalidLength] if either has more than `N` coefficients.
+    pub fn from_parts(u: Vec<Zq::I>, v: Vec<Zq::I>) -> Result<Self, Error> {
+        if u.len() > N || v.len() > N {
+            return Err(Error::InvalidLength);
+        }
+        let mut u = Polynomial::new(u);
+        let mut v = Polynomial::new(v);
+        u.coeffs_mut(|c| *c = Zq::modulo(c));
+        v.coeffs_mut(|c| *c = Zq::modulo(c));
+        Ok(Self { u, v })
+    }
+
+    /// Multiplies the ciphertext by the integer `k`. The result decrypts to `k * m mod 2`
+    /// where `m` is the original message, provided the noise (also multiplied by `k`)
+    /// stays below `Q/4`.
+    pub fn mul_scalar(&self, k: &Zq::I) -> Self {
+        let mut c = Self {
+            u: self.u.clone(),
+            v: self.v.clone(),
+        };
+        c.mul_scalar_assign(k);
+        c
+    }
+
+    /// Multiplies the ciphertext by the cleartext `bit`, i.e. returns the ciphertext of zeros
+    /// (with zero noise) if `bit` is false, or the ciphertext unchanged if it is true. It is
+    /// useful to mask or select ciphertexts with public bits.
+    pub fn mul_plain_bit(self, bit: bool) -> Self {
+        if bit {
+            self
+        } else {
+            Self {
+                u: Polynomial::new(Vec::<Zq::I>::new()),
+                v: Polynomial::new(Vec::<Zq::I>::new()),
+            }
+        }
+    }
+
+    /// Multiplies both components of the ciphertext by the cleartext scalar `k` and reduces
+    /// them modulo q, consuming the ciphertext. The result decrypts to `k * m mod 2`.
+    ///
+    /// The noise is multiplied by `|k|`, so it exceeds the decryption bound `Q/4` quickly as
+    /// `k` grows. See [CipherText::mul_scalar] for the version borrowing the ciphertext.
+    pub fn mul_plain_scalar(self, k: Zq::I) -> Self {
+        let mut c = self;
+        c.mul_scalar_assign(&k);
+        c
+    }
+
+    /// Multiplies the ciphertext by the integer `k` in place, without allocating a new
+    /// ciphertext. See [CipherText::mul_scalar].
+    pub fn mul_scalar_assign(&mut self, k: &Zq::I) {
+        let mul_mod = |c: &mut Zq::I| *c = Zq::modulo(&(k.clone() * c.clone()));
+        self.u.coeffs_mut(mul_mod);
+        self.v.coeffs_mut(mul_mod);
+    }
+
+    /// Multiplies the ciphertext by the plaintext polynomial with coefficients `p`. The result
+    /// decrypts to `m * p mod 2` where `m` is the original message, provided the noise
+    /// (multiplied by up to the sum of the absolute coefficients of `p`) stays below `Q/4`.
+    ///
+    /// ## Safety
+    /// The length of `p` must be less than or equal to `N`. Use [CipherText::try_mul_plaintext]
+    /// for untrusted input.
+    pub fn mul_plaintext(&self, p: &[Zq::I]) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.try_mul_plaintext(p)
+            .expect("plaintext length must be at most N")
+    }
+
+    /// Multiplies the ciphertext by the plaintext polynomial with coefficients `p`. Returns
+    /// [Error::InvalidLength] if the length of `p` is larger than `N`.
+    pub fn try_mul_plaintext(&self, p: &[Zq::I]) -> Result<Self, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        if p.len() > N {
+            return Err(Error::InvalidLength);
+        }
+        let p = Polynomial::<Zq::I, N>::new(p.to_vec());
+        Ok(Self {
+            u: modulo_coefficients::<Zq, N>(self.u.clone() * p.clone()),
+            v: modulo_coefficients::<Zq, N>(self.v.clone() * p),
+        })
+    }
+
+    /// Computes the dot product of the encrypted message with the public binary `mask`, i.e.
+    /// the sum modulo 2 of the message bits selected by the mask. The sum is in the constant
+    /// coefficient of the resulting ciphertext, and the other coefficients are meaningless.
+    ///
+    /// It multiplies the ciphertext by the plaintext polynomial `p` with `p_0 = mask_0` and
+    /// `p_(N-i) = mask_i`, so the noise grows by up to the number of selected bits (see
+    /// [CipherText::mul_plaintext]).
+    ///
+    /// ## Safety
+    /// The length of `mask` must be less than or equal to `N`. Use [CipherText::try_masked_sum]
+    /// for untrusted input.
+    pub fn masked_sum(&self, mask: &[bool]) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.try_masked_sum(mask)
+            .expect("mask length must be at most N")
+    }
+
+    /// Computes the dot product of the encrypted message with the public binary `mask`. Returns
+    /// [Error::InvalidLength] if the length of `mask` is larger than `N`. See
+    /// [CipherText::masked_sum].
+    pub fn try_masked_sum(&self, mask: &[bool]) -> Result<Self, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        if mask.len() > N {
+            return Err(Error::InvalidLength);
+        }
+        // in Zq[x]/(x^N+1), the constant coefficient of m * p is m_0 p_0 - sum m_i p_(N-i),
+        // where the sign does not matter modulo 2.
+        let mut p = vec![Zq::I::zero(); N];
+        mask.iter()
+            .enumerate()
+            .filter(|(_, &selected)| selected)
+            .for_each(|(i, _)| p[(N - i) % N] = Zq::I::one());
+        self.try_mul_plaintext(&p)
+    }
+
+    /// Returns the ciphertext with `u = v = 0`. It decrypts to the all-zero message and is
+    /// the identity of the homomorphic addition, e.g. the initial value when summing up
+    /// ciphertexts (see the [Sum] implementation).
+    ///
+    /// Note that it is not an encryption and must not be sent as one, since anyone can see
+    /// that it encrypts zero.
+    pub fn zero() -> Self {
+        Self {
+            u: Polynomial::new(Vec::<Zq::I>::new()),
+            v: Polynomial::new(Vec::<Zq::I>::new()),
+        }
+    }
+
+    /// Adds the two ciphertexts. The result decrypts to the XOR of the two messages,
+    /// provided the sum of the noises stays below `Q/4`. See
+    /// [max_homomorphic_additions](crate::max_homomorphic_additions) for how many
+    /// ciphertexts can be summed up.
+    pub fn add_ciphertext(&self, other: &Self) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        Self {
+            u: modulo_coefficients::<Zq, N>(self.u.clone() + other.u.clone()),
+            v: modulo_coefficients::<Zq, N>(self.v.clone() + other.v.clone()),
+        }
+    }
+
+    /// Adds the plaintext message `m` to the ciphertext. The result decrypts to the XOR of
+    /// the original message and `m`. The noise is not increased.
+    pub fn add_plaintext(&self, m: Message<Zq, N>) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let q_div_2_m = scale_coefficients::<Zq, N>(Polynomial::from_coeffs(m.data));
+        Self {
+            u: self.u.clone(),
+            v: modulo_coefficients::<Zq, N>(self.v.clone() + q_div_2_m),
+        }
+    }
+
+    /// Computes the homomorphic bitwise NOT of the ciphertext, i.e. the result decrypts to
+    /// `1 - b` for each of the `N` bits `b` of the original message. It is the same as adding
+    /// the all-ones plaintext (see [CipherText::add_plaintext]).
+    pub fn not(&self) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.add_plaintext(Message {
+            data: vec![Zq::I::one(); N],
+        })
+    }
+
+    /// Blinds the ciphertext by adding a random plaintext mask of length `N` (see
+    /// [CipherText::add_plaintext]). The mask is written to `mask_out`, replacing its content,
+    /// so that the decrypted result can be unblinded with [DecryptKey::unblind](crate::DecryptKey::unblind).
+    ///
+    /// The blinded ciphertext reveals nothing about the original message to a party
+    /// decrypting it without knowing the mask.
+    pub fn blind(&self, rng: &mut impl Rng, mask_out: &mut Vec<Zq::I>) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let mask = Message::<Zq, N>::random(rng, N);
+        mask_out.clear();
+        mask_out.extend_from_slice(&mask.data);
+        self.add_plaintext(mask)
+    }
+
+    /// Counts the coefficients that differ between the two ciphertexts, across both
+    /// polynomials `u` and `v`. The result is in the range `[0, 2N]`.
+    ///
+    /// This is useful for debugging, e.g. to check that two encryptions of the same
+    /// message are unlinkable, in which case almost all coefficients differ.
+    pub fn coefficient_diff_count(&self, other: &Self) -> usize {
+        let diff = |p1: &Polynomial<Zq::I, N>, p2: &Polynomial<Zq::I, N>| {
+            to_fixed_coeffs_vec::<Zq, N>(p1)
+                .iter()
+                .zip(to_fixed_coeffs_vec::<Zq, N>(p2).iter())
+                .filter(|(c1, c2)| c1 != c2)
+                .count()
+        };
+        diff(&self.u, &other.u) + diff(&self.v, &other.v)
+    }
+
+    /// Returns a 16-byte identifier of the ciphertext, i.e. the truncated SHAKE128 hash of
+    /// its compact serialization (see [CipherText::to_compact]). Equal ciphertexts have the
+    /// same identifier, e.g. for deduplication or as a cache key. Please note that encrypting
+    /// the same message twice gives different ciphertexts, and thus different identifiers.
+    pub fn id(&self) -> [u8; 16]
+    where
+        Zq::I: ToPrimitive,
+    {
+        let mut id = [0u8; 16];
+        XofRng::new(CIPHERTEXT_ID_DOMAIN, &self.to_compact()).fill_bytes(&mut id);
+        id
+    }
+
+    /// Estimates the Shannon entropy in bits of the distribution of the `2N` coefficients
+    /// of `u` and `v`, from their empirical frequencies. It helps to decide whether an
+    /// entropy-coded or sparse serialization (see [CipherText::to_sparse]) is worthwhile.
+    ///
+    /// The coefficients of a fresh ciphertext are close to uniform in `[0, Q)`, i.e. close to
+    /// `log2(Q)` bits, which is incompressible. Please note that the estimate is at most
+    /// `log2(2N)`, since there are only `2N` samples, so it approaches `log2(Q)` only for
+    /// `2N` much larger than `Q`. Structured ciphertexts (e.g. with many zero coefficients)
+    /// have a lower entropy.
+    pub fn coefficient_entropy(&self) -> f64 {
+        let mut coeffs = to_fixed_coeffs_vec::<Zq, N>(&self.u);
+        coeffs.extend(to_fixed_coeffs_vec::<Zq, N>(&self.v));
+        coeffs.sort();
+
+        let total = coeffs.len() as f64;
+        coeffs
+            .chunk_by(|a, b| a == b)
+            .map(|run| {
+                let p = run.len() as f64 / total;
+                -p * libm::log2(p)
+            })
+            .sum()
+    }
+
+    /// Switches the ciphertext from the modulus `Q` to the smaller modulus `Q'` of `Zq2`, by
+    /// scaling each coefficient `c` to `round(c * Q' / Q)`. It shrinks the compact
+    /// serialization to `ceil(log2(Q'))` bits per coefficient. Decrypt it with the key
+    /// switched by [DecryptKey::mod_switch](crate::DecryptKey::mod_switch).
+    ///
+    /// The decryption noise becomes `n * Q'/Q` plus the rounding error of at most
+    /// `(|s|_1 + 1) / 2`, i.e. `(N * B + 1) / 2`. The decryption is correct only if
+    ///
+    /// `|n| * Q'/Q + (N * B + 1) / 2 < Q'/4`,
+    ///
+    /// so `Q'` must stay well above `2N * B`, e.g. `Q' = 1601` for the standard parameters.
+    ///
+    /// ## Safety
+    /// `Zq2::Q` must be less than `Zq::Q`.
+    pub fn mod_switch<Zq2: IntField<I = Zq::I>>(self) -> CipherText<Zq2, N>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        let q = Zq::Q.to_i128().unwrap();
+        let q2 = Zq2::Q.to_i128().unwrap();
+        let switch = |p: Polynomial<Zq::I, N>| {
+            let coeffs = p
+                .iter()
+                .map(|c| {
+                    // round(c * q2 / q), rounding half up for negative c too
+                    let c = (2 * c.to_i128().unwrap() * q2 + q).div_euclid(2 * q);
+                    Zq2::modulo(&Zq::I::from_i128(c).unwrap())
+                })
+                .collect();
+            Polynomial::new(coeffs)
+        };
+        CipherText {
+            u: switch(self.u),
+            v: switch(self.v),
+        }
+    }
+
+    /// Returns true if both polynomials have at most `N` coefficients. The constructors and
+    /// the deserialization reject longer polynomials, so it is a defensive check.
+    pub(crate) fn is_well_formed(&self) -> bool {
+        self.u.iter().count() <= N && self.v.iter().count() <= N
+    }
+
+    /// Computes the HMAC-SHA3-256 of the compact serialization (see [CipherText::to_compact])
+    /// with the `key`, to detect tampering of the ciphertext in transit. Please note that a
+    /// ciphertext modified by homomorphic operations no longer verifies, so it is for use
+    /// when the homomorphism is not needed.
+    pub fn mac(&self, key: &[u8]) -> [u8; 32]
+    where
+        Zq::I: ToPrimitive,
+    {
+        self.hmac(key).finalize().into_bytes().into()
+    }
+
+    /// Verifies the `tag` computed by [CipherText::mac] with the `key`, in constant time.
+    pub fn verify_mac(&self, key: &[u8], tag: &[u8; 32]) -> bool
+    where
+        Zq::I: ToPrimitive,
+    {
+        self.hmac(key).verify_slice(tag).is_ok()
+    }
+
+    /// Returns the HMAC state with the compact serialization absorbed.
+    fn hmac(&self, key: &[u8]) -> Hmac<Sha3_256>
+    where
+        Zq::I: ToPrimitive,
+    {
+        // HMAC accepts keys of any length
+        let mut hmac = Hmac::<Sha3_256>::new_from_slice(key).unwrap();
+        hmac.update(&self.to_compact());
+        hmac
+    }
+
+    /// Serializes the ciphertext into the compact bit-packed format.
+    pub fn to_compact(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        pack_coefficients::<Zq, N>(&[&self.u, &self.v])
+    }
+
+    /// Deserializes a ciphertext from the compact bit-packed format produced by
+    /// [CipherText::to_compact].
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        let mut polys =
+            unpack_coefficients::<Zq, N>(bytes, 2).ok_or(Error::InvalidCipherTextBytes)?;
+        let v = polys.pop().unwrap();
+        let u = polys.pop().unwrap();
+        Ok(Self { u, v })
+    }
+
+    /// Serializes the ciphertext into the fixed-width format: the `N` coefficients of `u`
+    /// followed by the `N` coefficients of `v`, each in the symmetric representation
+    /// `[-(Q-1)/2, Q/2]` as a signed (two's complement) little-endian integer of
+    /// `size_of::<I>()` bytes, e.g. 4 bytes for `i32`. The total length is
+    /// `2 * N * size_of::<I>()` bytes, without any header.
+    ///
+    /// The integer type `I` must be a primitive integer of at most 128 bits.
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        to_fixed_width_bytes::<Zq, N>(&[&self.u, &self.v])
+    }
+
+    /// Deserializes a ciphertext from the fixed-width format produced by
+    /// [CipherText::to_bytes]. Returns [DecodeError::WrongLength] if the length is not
+    /// exactly `2 * N * size_of::<I>()`, or [DecodeError::InvalidCoefficient] if a
+    /// coefficient is not in the symmetric representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        Zq::I: FromPrimitive,
+    {
+        let mut polys = from_fixed_width_bytes::<Zq, N>(bytes, 2)?;
+        let v = polys.pop().unwrap();
+        let u = polys.pop().unwrap();
+        Ok(Self { u, v })
+    }
+
+    /// Serializes the ciphertext into the sparse format if it is smaller than the compact
+    /// format, or into the compact format otherwise. The first byte tells the format:
+    /// - `0`: followed by the compact format (see [CipherText::to_compact]).
+    /// - `1`: for each polynomial `u` and `v`, the number of nonzero coefficients as a
+    ///   4-byte little-endian integer, followed by each nonzero coefficient as its 4-byte
+    ///   little-endian index and its value in `[0, Q)` as `ceil(ceil(log2(Q)) / 8)`
+    ///   little-endian bytes, in increasing order of the indices.
+    pub fn to_sparse(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        let value_size = coefficient_bit_width::<Zq>().div_ceil(8);
+        let nonzeros = |p: &Polynomial<Zq::I, N>| {
+            to_fixed_coeffs_vec::<Zq, N>(p)
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as u32, c.mod_floor(&Zq::Q).to_u64().unwrap()))
+                .filter(|(_, c)| *c != 0)
+                .collect::<Vec<_>>()
+        };
+        let (u, v) = (nonzeros(&self.u), nonzeros(&self.v));
+
+        let sparse_size = 8 + (u.len() + v.len()) * (4 + value_size);
+        if sparse_size >= ciphertext_size::<Zq, N>() {
+            let mut bytes = vec![DENSE_TAG];
+            bytes.extend(self.to_compact());
+            return bytes;
+        }
+
+        let mut bytes = Vec::with_capacity(1 + sparse_size);
+        bytes.push(SPARSE_TAG);
+        for entries in [u, v] {
+            bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+            for (i, c) in entries {
+                bytes.extend_from_slice(&i.to_le_bytes());
+                bytes.extend_from_slice(&c.to_le_bytes()[..value_size]);
+            }
+        }
+        bytes
+    }
+
+    /// Deserializes a ciphertext from the format produced by [CipherText::to_sparse].
+    pub fn from_sparse(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        match bytes.split_first() {
+            Some((&DENSE_TAG, rest)) => Self::from_compact(rest),
+            Some((&SPARSE_TAG, rest)) => {
+                let mut rest = rest;
+                let u = read_sparse_polynomial::<Zq, N>(&mut rest)
+                    .ok_or(Error::InvalidCipherTextBytes)?;
+                let v = read_sparse_polynomial::<Zq, N>(&mut rest)
+                    .ok_or(Error::InvalidCipherTextBytes)?;
+                if !rest.is_empty() {
+                    return Err(Error::InvalidCipherTextBytes);
+                }
+                Ok(Self { u, v })
+            }
+            _ => Err(Error::InvalidCipherTextBytes),
+        }
+    }
+
+    /// Serializes the ciphertext into type-length-value (TLV) records, so that fields can be
+    /// added in the future without breaking older parsers. Each record is a 1-byte type, a
+    /// 4-byte little-endian length of the value, and the value. The ciphertext consists of
+    /// the mandatory records:
+    /// - type `1`: the polynomial `u` in the compact format (see [CipherText::to_compact]).
+    /// - type `2`: the polynomial `v` in the compact format.
+    pub fn to_tlv(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        let mut bytes = Vec::with_capacity(2 * TLV_HEADER_SIZE + ciphertext_size::<Zq, N>());
+        for (tlv_type, p) in [(TLV_TYPE_U, &self.u), (TLV_TYPE_V, &self.v)] {
+            let value = pack_coefficients::<Zq, N>(&[p]);
+            bytes.push(tlv_type);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend(value);
+        }
+        bytes
+    }
+
+    /// Deserializes a ciphertext from the TLV records produced by [CipherText::to_tlv].
+    /// Records of unknown types are skipped. Returns [Error::InvalidCipherTextBytes] if a
+    /// record is truncated, or the record `u` or `v` is missing, repeated or malformed.
+    pub fn from_tlv(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        let (mut u, mut v) = (None, None);
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let (tlv_type, value) =
+                read_tlv_record(&mut rest).ok_or(Error::InvalidCipherTextBytes)?;
+            let field = match tlv_type {
+                TLV_TYPE_U => &mut u,
+                TLV_TYPE_V => &mut v,
+                _ => continue,
+            };
+            let mut polys =
+                unpack_coefficients::<Zq, N>(value, 1).ok_or(Error::InvalidCipherTextBytes)?;
+            if field.replace(polys.pop().unwrap()).is_some() {
+                return Err(Error::InvalidCipherTextBytes);
+            }
+        }
+
+        match (u, v) {
+            (Some(u), Some(v)) => Ok(Self { u, v }),
+            _ => Err(Error::InvalidCipherTextBytes),
+        }
+    }
+}
+
+/// Adds the two ciphertexts, i.e. `u1 + u2` and `v1 + v2` modulo `Q`. The result decrypts to
+/// the bitwise XOR of the two messages. See [CipherText::add_ciphertext].
+///
+/// The noise of the sum is the sum of the noises, so its variance doubles with each addition
+/// of fresh ciphertexts, and the decryption fails once a coefficient of the noise exceeds
+/// `Q/4`. See [max_homomorphic_additions](crate::max_homomorphic_additions).
+impl<Zq: IntField, const N: usize> Add for CipherText<Zq, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.add_ciphertext(&other)
+    }
+}
+
+/// Negates the ciphertext, i.e. `-u` and `-v` modulo `Q`. The result decrypts to the same
+/// message as the original ciphertext, so `c + (-c)` decrypts to the all-zero message, as
+/// subtraction equals addition (XOR) for bits.
+///
+/// The decryption computes `-(v - u*s) = -e - [Q/2]m`, i.e. both the noise and the embedded
+/// plaintext change sign. Since the rounding only compares `|v - u*s|` with the threshold
+/// (see [IntField::decrypt_threshold]), and the symmetric representation of `-x` is `-x`
+/// except for `x = Q/2` with an even `Q` (where `-x ≡ x`), every bit is recovered for any
+/// `Q`, odd or even. The magnitude of the noise is unchanged.
+impl<Zq: IntField, const N: usize> Neg for CipherText<Zq, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let (mut u, mut v) = (self.u, self.v);
+        u.coeffs_mut(|c| *c = -c.clone());
+        v.coeffs_mut(|c| *c = -c.clone());
+        Self {
+            u: modulo_coefficients::<Zq, N>(u),
+            v: modulo_coefficients::<Zq, N>(v),
+        }
+    }
+}
+
+/// Compares the polynomials `u` and `v` in constant time, unlike the derived [PartialEq]
+/// which returns at the first differing coefficient. The result agrees with `==`.
+///
+/// Only the comparison is hardened. The conversions of the coefficients of the type `I`
+/// are not guaranteed to be constant-time.
+#[cfg(feature = "constant-time")]
+impl<Zq: IntField, const N: usize> subtle::ConstantTimeEq for CipherText<Zq, N> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        crate::polynomial::ct_eq_polynomials::<Zq, N>(&self.u, &other.u)
+            & crate::polynomial::ct_eq_polynomials::<Zq, N>(&self.v, &other.v)
+    }
+}
+
+/// Sums up the ciphertexts starting from [CipherText::zero]. The result decrypts to the XOR
+/// of all the messages, and the sum of an empty iterator decrypts to the all-zero message.
+///
+/// The noise of the sum is the sum of the noises of the summands, so a very long
+/// accumulation can exceed the noise budget and fail to decrypt. See
+/// [max_homomorphic_additions](crate::max_homomorphic_additions).
+impl<Zq: IntField, const N: usize> Sum for CipherText<Zq, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    fn sum<It: Iterator<Item = Self>>(iter: It) -> Self {
+        iter.fold(Self::zero(), |acc, c| acc.add_ciphertext(&c))
+    }
+}
+
+/// The format tag of [CipherText::to_sparse] for the compact format.
+const DENSE_TAG: u8 = 0;
+/// The format tag of [CipherText::to_sparse] for the sparse format.
+const SPARSE_TAG: u8 = 1;
+
+/// The number of bytes of the type and the length of a record in [CipherText::to_tlv].
+const TLV_HEADER_SIZE: usize = 5;
+/// The record type of [CipherText::to_tlv] for the polynomial `u`.
+const TLV_TYPE_U: u8 = 1;
+/// The record type of [CipherText::to_tlv] for the polynomial `v`.
+const TLV_TYPE_V: u8 = 2;
+
+/// Reads a record in the format of [CipherText::to_tlv] and advances `bytes`. Returns the
+/// type and the value, or `None` if the record is truncated.
+fn read_tlv_record<'a>(bytes: &mut &'a [u8]) -> Option<(u8, &'a [u8])> {
+    let (header, rest) = bytes.split_at_checked(TLV_HEADER_SIZE)?;
+    let len = u32::from_le_bytes(header[1..].try_into().unwrap()) as usize;
+    let (value, rest) = rest.split_at_checked(len)?;
+    *bytes = rest;
+    Some((header[0], value))
+}
+
+/// Reads a polynomial in the sparse format of [CipherText::to_sparse] and advances `bytes`.
+/// Returns `None` if the bytes are truncated, the indices are not increasing and less than
+/// `N`, or a value is zero or not less than `Q`.
+fn read_sparse_polynomial<Zq: IntField, const N: usize>(
+    bytes: &mut &[u8],
+) -> Option<Polynomial<Zq::I, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+{
+    let value_size = coefficient_bit_width::<Zq>().div_ceil(8);
+    let q = Zq::Q.to_u64().unwrap();
+    let mut take = |len: usize| {
+        let (head, tail) = bytes.split_at_checked(len)?;
+        *bytes = tail;
+        Some(head)
+    };
+
+    let count = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+    if count > N {
+        return None;
+    }
+    let mut coeffs = vec![Zq::I::zero(); N];
+    let mut next_index = 0;
+    for _ in 0..count {
+        let i = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+        let mut value = [0u8; 8];
+        value[..value_size].copy_from_slice(take(value_size)?);
+        let c = u64::from_le_bytes(value);
+        if i < next_index || i >= N || c == 0 || c >= q {
+            return None;
+        }
+        coeffs[i] = Zq::modulo(&Zq::I::from_u64(c)?);
+        next_index = i + 1;
+    }
+    Some(Polynomial::new(coeffs))
+}
+
+/// Computes the encryption of the weighted sum `sum_i w_i * m_i` of the messages `m_i` of the
+/// ciphertexts `cs`, with the public `weights`, by [CipherText::mul_scalar] and
+/// [CipherText::add_ciphertext].
+///
+/// The plaintext modulus is 2, so the result decrypts to the weighted sum modulo 2 bitwise,
+/// i.e. only the parity of each weight matters for the message. The noise grows to up to
+/// `sum_i |w_i|` times the noise of a fresh ciphertext and must stay below `Q/4`, so the
+/// weights should be small (e.g. reduced to `{0, 1}` or `{-1, 0, 1}`).
+///
+/// ## Safety
+/// The lengths of `cs` and `weights` must be equal. Use [try_weighted_sum] for untrusted input.
+pub fn weighted_sum<Zq: IntField, const N: usize>(
+    cs: &[CipherText<Zq, N>],
+    weights: &[Zq::I],
+) -> CipherText<Zq, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    try_weighted_sum(cs, weights).expect("the number of weights must match the ciphertexts")
+}
+
+/// Computes the encryption of the weighted sum of the messages, see [weighted_sum]. Returns
+/// [Error::InvalidLength] if the lengths of `cs` and `weights` are different.
+pub fn try_weighted_sum<Zq: IntField, const N: usize>(
+    cs: &[CipherText<Zq, N>],
+    weights: &[Zq::I],
+) -> Result<CipherText<Zq, N>, Error>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    if cs.len() != weights.len() {
+        return Err(Error::InvalidLength);
+    }
+    let zero = CipherText {
+        u: Polynomial::zero(),
+        v: Polynomial::zero(),
+    };
+    Ok(cs
+        .iter()
+        .zip(weights)
+        .fold(zero, |sum, (c, w)| sum.add_ciphertext(&c.mul_scalar(w))))
+}
+
+/// Computes the Pearson correlation of the `2N` coefficients of `u` and `v` of the two
+/// ciphertexts, in the range `[-1, 1]`. It returns `0` if the coefficients of either
+/// ciphertext are all equal.
+///
+/// This is a statistical diagnostic, e.g. for auditing a mixnet: a re-randomized ciphertext
+/// (e.g. added to a fresh encryption of zero) should be close to `0` with its input, while a
+/// ciphertext that is only partially modified (e.g. by [CipherText::add_plaintext], which
+/// keeps `u`) is clearly correlated. A low correlation does not prove unlinkability.
+pub fn ciphertext_correlation<Zq: IntField, const N: usize>(
+    a: &CipherText<Zq, N>,
+    b: &CipherText<Zq, N>,
+) -> f64
+where
+    Zq::I: ToPrimitive,
+{
+    let coeffs = |c: &CipherText<Zq, N>| {
+        to_fixed_coeffs_vec::<Zq, N>(&c.u)
+            .iter()
+            .chain(to_fixed_coeffs_vec::<Zq, N>(&c.v).iter())
+            .map(|x| x.to_f64().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let (xs, ys) = (coeffs(a), coeffs(b));
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    cov / libm::sqrt(var_x * var_y)
+}
+
+/// The domain separation tag for hashing the ciphertext in [CipherText::id].
+const CIPHERTEXT_ID_DOMAIN: &[u8] = b"rlwe-encryption/ciphertext-id";
+
+/// Returns the size in bytes of a ciphertext in the compact format (see [CipherText::to_compact]).
+///
+/// ```
+/// use rlwe_encryption::{ciphertext_size, StandardZq};
+///
+/// assert_eq!(ciphertext_size::<StandardZq, 256>(), 768);
+/// ```
+pub fn ciphertext_size<Zq: IntField, const N: usize>() -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    packed_size::<Zq, N>(2)
+}
@@ -0,0 +1,71 @@
+//! Defines the [KeyCodec] trait to abstract over the serialization formats.
+
+use alloc::vec::Vec;
+use num::{FromPrimitive, ToPrimitive};
+
+use crate::{CipherText, DecryptKey, EncryptKey, Error, IntField};
+
+/// A serialization format for keys and ciphertexts of type `T`.
+///
+/// It allows writing storage code which is generic over the format, e.g.
+///
+/// ```
+/// use rlwe_encryption::{CompactCodec, EncryptKey, KeyCodec, StandardZq};
+///
+/// fn store<C: KeyCodec<EncryptKey<StandardZq, 256>>>(ek: &EncryptKey<StandardZq, 256>) -> Vec<u8> {
+///     C::encode(ek)
+/// }
+///
+/// let (ek, _) = rlwe_encryption::standard(&mut rand::rng());
+/// let bytes = store::<CompactCodec>(&ek);
+/// assert!(CompactCodec::decode(&bytes).is_ok_and(|ek2: EncryptKey<_, 256>| ek.same_crs(&ek2)));
+/// ```
+pub trait KeyCodec<T> {
+    /// Encodes the value into bytes.
+    fn encode(value: &T) -> Vec<u8>;
+
+    /// Decodes the value from bytes produced by [KeyCodec::encode].
+    fn decode(bytes: &[u8]) -> Result<T, Error>;
+}
+
+/// The compact bit-packed format, i.e. `to_compact` and `from_compact`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactCodec;
+
+/// The JSON format, i.e. `to_json` and `from_json`, encoded in UTF-8.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonCodec;
+
+macro_rules! impl_codecs {
+    ($name:ident) => {
+        impl<Zq: IntField, const N: usize> KeyCodec<$name<Zq, N>> for CompactCodec
+        where
+            Zq::I: ToPrimitive + FromPrimitive,
+        {
+            fn encode(value: &$name<Zq, N>) -> Vec<u8> {
+                value.to_compact()
+            }
+
+            fn decode(bytes: &[u8]) -> Result<$name<Zq, N>, Error> {
+                $name::from_compact(bytes)
+            }
+        }
+
+        #[cfg(feature = "json")]
+        impl<Zq: IntField, const N: usize> KeyCodec<$name<Zq, N>> for JsonCodec {
+            fn encode(value: &$name<Zq, N>) -> Vec<u8> {
+                value.to_json().into_bytes()
+            }
+
+            fn decode(bytes: &[u8]) -> Result<$name<Zq, N>, Error> {
+                let json = core::str::from_utf8(bytes).map_err(|_| Error::InvalidJson)?;
+                $name::from_json(json)
+            }
+        }
+    };
+}
+
+impl_codecs!(EncryptKey);
+impl_codecs!(DecryptKey);
+impl_codecs!(CipherText);
@@ -0,0 +1,89 @@
+//! Defines the [CompactCipherText] wrapper for bit-packed serde serialization.
+
+use alloc::vec::Vec;
+use num::{FromPrimitive, One, ToPrimitive};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{CipherText, IntField};
+
+/// A wrapper of [CipherText] which is serialized with serde in the compact bit-packed
+/// format (see [CipherText::to_compact]) instead of one full integer per coefficient,
+/// e.g. 768 bytes instead of 2048 bytes for a standard ciphertext.
+///
+/// The coefficients must be in the range `[-Q/2, Q/2]`, which holds for ciphertexts produced
+/// by this library. Otherwise, the serialization returns an error.
+///
+/// ```
+/// use rlwe_encryption::{CompactCipherText, Message, StandardZq};
+///
+/// let rng = &mut rand::rng();
+/// let (ek, _) = rlwe_encryption::standard(rng);
+/// let c = ek.encrypt(rng, Message::new(vec![1, 0, 1]));
+/// let bytes = bincode::serialize(&CompactCipherText(c.clone())).unwrap();
+/// let decoded: CompactCipherText<StandardZq, 256> = bincode::deserialize(&bytes).unwrap();
+/// assert!(decoded.0 == c);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct CompactCipherText<Zq: IntField, const N: usize>(pub CipherText<Zq, N>);
+
+impl<Zq: IntField, const N: usize> Serialize for CompactCipherText<Zq, N>
+where
+    Zq::I: ToPrimitive,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let half_q = Zq::Q / (Zq::I::one() + Zq::I::one());
+        let in_range = self.0.is_well_formed()
+            && self
+                .0
+                .u
+                .iter()
+                .chain(self.0.v.iter())
+                .all(|c| c >= &-half_q.clone() && c <= &half_q);
+        if !in_range {
+            return Err(ser::Error::custom(
+                "ciphertext coefficient out of range [-Q/2, Q/2]",
+            ));
+        }
+        serializer.serialize_bytes(&self.0.to_compact())
+    }
+}
+
+impl<'de, Zq: IntField, const N: usize> Deserialize<'de> for CompactCipherText<Zq, N>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+        CipherText::from_compact(&bytes)
+            .map(CompactCipherText)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Visits the bytes of the compact format, either as a byte array or a sequence of bytes
+/// (e.g. for JSON).
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("the bytes of a compact ciphertext")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,66 @@
+//! Defines the common reference string and the digest binding a protocol to the parameters.
+
+use num::ToPrimitive;
+use poly_ring_xnp1::Polynomial;
+use sha3::{Digest, Sha3_256};
+
+use crate::{
+    intfield::IntField,
+    polynomial::{pack_coefficients, rand_polynomial},
+    xof::XofRng,
+    EncryptKey,
+};
+
+/// The domain separation tag for deriving the public polynomial `a` from a label.
+const LABEL_CRS_DOMAIN: &[u8] = b"rlwe-encryption/crs";
+
+/// The domain separation tag of [protocol_digest].
+const PROTOCOL_DIGEST_DOMAIN: &[u8] = b"rlwe-encryption/protocol-digest";
+
+/// The common reference string, i.e. the public polynomial `a` shared by the keys.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crs<Zq: IntField, const N: usize> {
+    pub(crate) a: Polynomial<Zq::I, N>,
+}
+
+impl<Zq: IntField, const N: usize> Crs<Zq, N> {
+    /// Derives the common reference string deterministically from a public `label`, as in
+    /// [key_gen_with_label](crate::key_gen_with_label).
+    pub fn from_label(label: &[u8]) -> Self {
+        Self {
+            a: rand_polynomial::<Zq, N>(&mut XofRng::new(LABEL_CRS_DOMAIN, label)),
+        }
+    }
+}
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Returns the common reference string, i.e. the public polynomial `a` of the key.
+    pub fn crs(&self) -> Crs<Zq, N> {
+        Crs { a: self.a.clone() }
+    }
+}
+
+/// Computes the SHA3-256 digest of the parameters `N`, `Q`, `B` and the common reference
+/// string `a`. The parties of a protocol can compare the digests, or include it in their
+/// transcripts, to confirm that they use identical parameters and prevent cross-protocol
+/// attacks.
+///
+/// ```
+/// use rlwe_encryption::{protocol_digest, Crs, StandardZq};
+///
+/// let crs = Crs::<StandardZq, 256>::from_label(b"my protocol");
+/// assert_eq!(protocol_digest(&crs), protocol_digest(&crs.clone()));
+/// ```
+pub fn protocol_digest<Zq: IntField, const N: usize>(crs: &Crs<Zq, N>) -> [u8; 32]
+where
+    Zq::I: ToPrimitive,
+{
+    let mut hasher = Sha3_256::new();
+    hasher.update(PROTOCOL_DIGEST_DOMAIN);
+    hasher.update((N as u64).to_le_bytes());
+    hasher.update(Zq::Q.to_u64().unwrap().to_le_bytes());
+    hasher.update(Zq::B.to_u64().unwrap().to_le_bytes());
+    hasher.update(pack_coefficients::<Zq, N>(&[&crs.a]));
+    hasher.finalize().into()
+}
@@ -1,12 +1,21 @@
 //! Defines the decryption key and the decryption method.
 
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Neg, Sub};
+use num::{FromPrimitive, Integer, One, Signed, ToPrimitive, Zero};
 use poly_ring_xnp1::Polynomial;
-use std::ops::{Add, Mul, Neg, Sub};
+use rand::Rng;
 
 use crate::{
     ciphertext::CipherText,
-    intfield::IntField,
-    polynomial::{modulo_coefficients, round_coefficients, to_fixed_coeffs_vec},
+    intfield::{to_standard_rep, IntField},
+    polynomial::{
+        from_fixed_width_bytes, modulo_coefficients, mul_polynomials, pack_coefficients,
+        packed_size, round_coefficients, round_coefficients_with_threshold, scale_coefficients,
+        small_polynomial, to_fixed_coeffs_vec, to_fixed_width_bytes, unpack_coefficients,
+    },
+    xof::XofRng,
+    DecodeError, EncryptKey, Error, KeyError, Message,
 };
 
 /// The decryption key created by the key generation method.
@@ -20,26 +29,545 @@ pub struct DecryptKey<Zq: IntField, const N: usize> {
     pub(crate) s: Polynomial<Zq::I, N>,
 }
 
+/// Zeroes each coefficient of the secret polynomial `s`.
+#[cfg(feature = "zeroize")]
+impl<Zq: IntField, const N: usize> zeroize::Zeroize for DecryptKey<Zq, N> {
+    fn zeroize(&mut self) {
+        self.s.coeffs_mut(|c| c.zeroize());
+    }
+}
+
+/// Wipes the secret polynomial `s` when the key is dropped.
+#[cfg(feature = "zeroize")]
+impl<Zq: IntField, const N: usize> Drop for DecryptKey<Zq, N> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<Zq: IntField, const N: usize> zeroize::ZeroizeOnDrop for DecryptKey<Zq, N> {}
+
+/// Compares the secrets `s` in constant time, unlike the derived [PartialEq] which returns
+/// at the first differing coefficient. The result agrees with `==`.
+///
+/// Only the comparison is hardened. The conversions of the coefficients of the type `I`
+/// are not guaranteed to be constant-time.
+#[cfg(feature = "constant-time")]
+impl<Zq: IntField, const N: usize> subtle::ConstantTimeEq for DecryptKey<Zq, N> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        crate::polynomial::ct_eq_polynomials::<Zq, N>(&self.s, &other.s)
+    }
+}
+
 impl<Zq: IntField, const N: usize> DecryptKey<Zq, N> {
+    /// Creates a key from the coefficients of the secret polynomial `s`, e.g. loaded from a
+    /// custom storage format. The coefficients are reduced modulo q. Returns
+    /// [KeyError::InvalidLength] if it does not have exactly `N` coefficients.
+    pub fn from_parts(s: Vec<Zq::I>) -> Result<Self, KeyError> {
+        if s.len() != N {
+            return Err(KeyError::InvalidLength {
+                expected: N,
+                actual: s.len(),
+            });
+        }
+        Ok(Self {
+            s: modulo_coefficients::<Zq, N>(Polynomial::new(s)),
+        })
+    }
+
+    /// Returns the `N` coefficients of the secret polynomial `s`, the inverse of
+    /// [DecryptKey::from_parts]. The copy is wiped when it is dropped.
+    ///
+    /// It is returned by value, since [Polynomial] trims the trailing zero coefficients and
+    /// does not expose its coefficients as a slice.
+    #[cfg(feature = "zeroize")]
+    pub fn secret(&self) -> zeroize::Zeroizing<Vec<Zq::I>> {
+        zeroize::Zeroizing::new(to_fixed_coeffs_vec::<Zq, N>(&self.s))
+    }
+
+    /// Returns the `N` coefficients of the secret polynomial `s`, the inverse of
+    /// [DecryptKey::from_parts]. With the `zeroize` feature, the copy is wiped when it is
+    /// dropped.
+    ///
+    /// It is returned by value, since [Polynomial] trims the trailing zero coefficients and
+    /// does not expose its coefficients as a slice.
+    #[cfg(not(feature = "zeroize"))]
+    pub fn secret(&self) -> Vec<Zq::I> {
+        to_fixed_coeffs_vec::<Zq, N>(&self.s)
+    }
+
+    /// Switches the key to the field `Zq2`, to decrypt the ciphertexts switched with
+    /// [CipherText::mod_switch]. The secret is small, so its coefficients are unchanged.
+    pub fn mod_switch<Zq2: IntField<I = Zq::I>>(&self) -> DecryptKey<Zq2, N> {
+        DecryptKey {
+            s: modulo_coefficients::<Zq2, N>(self.s.clone()),
+        }
+    }
+
+    /// Samples a random secret `s` with coefficients in `[-B, B]`, as in the key generation,
+    /// but without computing the encryption key. It is intended for simulations that only
+    /// need the secret, e.g. analyzing the distribution of secrets.
+    pub fn random_secret(rng: &mut impl Rng) -> Self {
+        Self {
+            s: small_polynomial::<Zq, N>(rng),
+        }
+    }
+
+    /// Serializes the key into the compact bit-packed format, in which each coefficient
+    /// is stored with `ceil(log2(Q))` bits.
+    pub fn to_compact(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        pack_coefficients::<Zq, N>(&[&self.s])
+    }
+
+    /// Deserializes a key from the compact bit-packed format produced by
+    /// [DecryptKey::to_compact]. The coefficients of the secret must be in the range `[-B, B]`.
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        let s = unpack_coefficients::<Zq, N>(bytes, 1)
+            .and_then(|mut polys| polys.pop())
+            .filter(|s| s.iter().all(|c| c.abs() <= Zq::B))
+            .ok_or(Error::InvalidKeyBytes)?;
+        Ok(Self { s })
+    }
+
+    /// Serializes the key into the fixed-width format: the `N` coefficients of the secret
+    /// `s`, each as a signed (two's complement) little-endian integer of `size_of::<I>()`
+    /// bytes. The total length is `N * size_of::<I>()` bytes, without any header. See
+    /// [CipherText::to_bytes].
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        to_fixed_width_bytes::<Zq, N>(&[&self.s])
+    }
+
+    /// Deserializes a key from the fixed-width format produced by [DecryptKey::to_bytes].
+    /// Returns [DecodeError::WrongLength] if the length is not exactly `N * size_of::<I>()`,
+    /// or [DecodeError::InvalidCoefficient] if a coefficient is not in the range `[-B, B]`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        Zq::I: FromPrimitive,
+    {
+        let s = from_fixed_width_bytes::<Zq, N>(bytes, 1)?.pop().unwrap();
+        if let Some(index) = s.iter().position(|c| c.abs() > Zq::B) {
+            return Err(DecodeError::InvalidCoefficient { index });
+        }
+        Ok(Self { s })
+    }
+
+    /// Evolves the key deterministically into a new secret `s'` sampled from the SHAKE128
+    /// hash of `s`. The matching encryption key is given by [DecryptKey::evolve_encrypt_key].
+    ///
+    /// This provides forward secrecy: since the hash cannot be inverted, compromising the
+    /// evolved key does not reveal the previous keys, hence the ciphertexts encrypted under
+    /// them cannot be decrypted with it. It relies on the caller to securely erase the
+    /// previous key, and to decrypt (or re-encrypt, see [ReKey](crate::ReKey)) the ciphertexts
+    /// still needed before evolving. Please note that it provides no post-compromise security:
+    /// anyone holding a key can compute all of its future evolutions.
+    pub fn evolve(&self) -> Self
+    where
+        Zq::I: ToPrimitive,
+    {
+        let rng = &mut self.evolve_rng();
+        Self {
+            s: small_polynomial::<Zq, N>(rng),
+        }
+    }
+
+    /// Returns the encryption key matching the evolved key [DecryptKey::evolve], i.e.
+    /// `t' = a * s' + e'` with the public polynomial `a` of `ek`, and the noise `e'` also
+    /// derived from the hash of `s`.
+    pub fn evolve_encrypt_key(&self, ek: &EncryptKey<Zq, N>) -> EncryptKey<Zq, N>
+    where
+        Zq::I: ToPrimitive,
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let rng = &mut self.evolve_rng();
+        let s = small_polynomial::<Zq, N>(rng);
+        let e = small_polynomial::<Zq, N>(rng);
+
+        // t' = a * s' + e'
+        let t = {
+            let a_s = mul_polynomials::<Zq, N>(&ek.a, &s);
+            modulo_coefficients::<Zq, N>(a_s + e)
+        };
+        EncryptKey { a: ek.a.clone(), t }
+    }
+
+    /// Returns the deterministic RNG from which the evolved key is sampled.
+    fn evolve_rng(&self) -> XofRng
+    where
+        Zq::I: ToPrimitive,
+    {
+        XofRng::new(EVOLVE_DOMAIN, &self.to_compact())
+    }
+
     /// Decrypts the given ciphertext into a vector of integers in {0, 1}.
     ///
     /// Please note that the length of the decrypted message is equal to `N`
     /// which can be larger than the original message length. The extended
     /// part is padded with zeros.
+    ///
+    /// A malformed ciphertext, i.e. with more than `N` coefficients in a polynomial, does not
+    /// panic but returns an all-zero message of length `N`, which cannot be told apart from
+    /// a valid encryption of zeros. Use [DecryptKey::try_decrypt] to reject it with
+    /// [Error::InvalidCipherText] instead. The serde deserialization already rejects such
+    /// ciphertexts with [Error::InvalidLength].
     pub fn decrypt(&self, c: CipherText<Zq, N>) -> Vec<Zq::I>
     where
         for<'a> &'a Zq::I:
             Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
     {
-        // m = v - u * s
-        let m = {
-            let u_s = modulo_coefficients::<Zq, N>(c.u.clone() * self.s.clone());
-            modulo_coefficients::<Zq, N>(c.v.clone() - u_s)
-        };
-
-        let mb = round_coefficients::<Zq, N>(m);
+        let mb = self.decrypt_polynomial(c);
 
         // Pad with zeros if the length is less than N (due to coefficients trimming in round_coefficients)
         to_fixed_coeffs_vec::<Zq, N>(&mb)
     }
+
+    /// Decrypts the given ciphertext like [DecryptKey::decrypt], but with a configurable
+    /// decision boundary: each coefficient `x` of `v - u * s` (see [DecryptKey::decrypt_raw])
+    /// decrypts to 1 if `|x| > threshold`, or to 0 otherwise, including `|x| == threshold`.
+    ///
+    /// [DecryptKey::decrypt] uses the threshold `q/4`, which is optimal when the noise is
+    /// symmetric. A lower threshold turns more coefficients into 1, a higher one into 0.
+    /// Decrypting at `q/4 - d` and `q/4 + d` flags the coefficients that differ as ambiguous
+    /// (within `d` of the boundary), e.g. as erasures for an error-correcting code.
+    pub fn decrypt_with_threshold(&self, c: CipherText<Zq, N>, threshold: &Zq::I) -> Vec<Zq::I>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let raw = self.raw_polynomial(c);
+        // the trimmed zero coefficients round like 0, i.e. to 1 for a negative threshold
+        let len = raw.iter().count();
+        let mb = round_coefficients_with_threshold::<Zq, N>(raw, threshold);
+        let mut m = to_fixed_coeffs_vec::<Zq, N>(&mb);
+        if Zq::I::zero() > *threshold {
+            m[len..].fill(Zq::I::one());
+        }
+        m
+    }
+
+    /// Decrypts the given ciphertext into a vector of integers in {0, 1}. Returns
+    /// [Error::InvalidCipherText] if the ciphertext is malformed, i.e. a polynomial has
+    /// more than `N` coefficients.
+    pub fn try_decrypt(&self, c: CipherText<Zq, N>) -> Result<Vec<Zq::I>, Error>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        if !c.is_well_formed() {
+            return Err(Error::InvalidCipherText);
+        }
+        Ok(self.decrypt(c))
+    }
+
+    /// Decrypts the ciphertext produced by [EncryptKey::encrypt_packed], and splits the bits
+    /// back into the messages with the given `lengths`.
+    ///
+    /// ## Safety
+    /// The sum of `lengths` must be less than or equal to `N`.
+    pub fn decrypt_packed(&self, c: CipherText<Zq, N>, lengths: &[usize]) -> Vec<Vec<Zq::I>>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let m = self.decrypt(c);
+        let mut rest = m.as_slice();
+        lengths
+            .iter()
+            .map(|&len| {
+                let (segment, tail) = rest.split_at(len);
+                rest = tail;
+                segment.to_vec()
+            })
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext without rounding, i.e. returns the `N` coefficients of
+    /// `v - u * s = [q/2] m + noise` in the symmetric representation `[-Q/2, Q/2]`.
+    pub fn decrypt_raw(&self, c: CipherText<Zq, N>) -> Vec<Zq::I>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        to_fixed_coeffs_vec::<Zq, N>(&self.raw_polynomial(c))
+    }
+
+    /// Decrypts the ciphertext produced by [EncryptKey::encrypt_field_elements] with the
+    /// plaintext modulus `t` into `N` field elements in `[0, t)`, by rounding `t/q` times
+    /// the coefficients of `v - u * s`.
+    pub fn decrypt_field_elements(&self, c: CipherText<Zq, N>, t: &Zq::I) -> Vec<Zq::I>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let q = Zq::Q.to_i128().unwrap();
+        let t = t.to_i128().unwrap();
+        self.decrypt_raw(c)
+            .into_iter()
+            .map(|x| {
+                let x = x.to_i128().unwrap().rem_euclid(q);
+                // round(t * x / q) mod t
+                let m = (2 * t * x + q) / (2 * q) % t;
+                Zq::I::from_i128(m).unwrap()
+            })
+            .collect()
+    }
+
+    /// Decrypts the ciphertext produced by [EncryptKey::encrypt_ternary] into `N` balanced
+    /// ternary symbols in `{-1, 0, 1}`, by rounding each coefficient of `v - u * s` to the
+    /// nearest of `0`, `[q/3]` and `2 * [q/3]`. See [EncryptKey::encrypt_ternary] for the
+    /// stricter noise bound `q/6`.
+    pub fn decrypt_ternary(&self, c: CipherText<Zq, N>) -> Vec<Zq::I>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let one = Zq::I::one();
+        let two = one.clone() + one.clone();
+        self.decrypt_field_elements(c, &(two.clone() + one.clone()))
+            .into_iter()
+            .map(|mi| if mi == two { -one.clone() } else { mi })
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext without rounding, like [DecryptKey::decrypt_raw], but
+    /// returns the coefficients in the standard representation `[0, Q)`, for interoperability
+    /// with tools expecting non-negative residues.
+    pub fn decrypt_raw_standard(&self, c: CipherText<Zq, N>) -> Vec<Zq::I>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        self.decrypt_raw(c)
+            .iter()
+            .map(to_standard_rep::<Zq>)
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext into soft values in `[0, 1]`, i.e. the distance of each
+    /// coefficient of [DecryptKey::decrypt_raw] from 0 normalized by `Q/2`. The values near
+    /// 0 and 1 are the confident decryptions of 0 and 1 respectively, while the values near
+    /// 0.5 are likely erroneous, for error-tolerant and probabilistic downstream processing.
+    pub fn decrypt_soft(&self, c: CipherText<Zq, N>) -> Vec<f64>
+    where
+        Zq::I: ToPrimitive,
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let q_div_2 = Zq::Q.to_f64().unwrap() / 2.0;
+        self.decrypt_raw(c)
+            .iter()
+            .map(|x| (x.abs().to_f64().unwrap() / q_div_2).min(1.0))
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext into a fixed-size array of integers in {0, 1}.
+    ///
+    /// It is equivalent to [DecryptKey::decrypt] but the result is returned on the
+    /// stack instead of a heap-allocated `Vec`. Please note that the polynomial
+    /// arithmetic underneath still allocates.
+    pub fn decrypt_array(&self, c: CipherText<Zq, N>) -> [Zq::I; N]
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let mb = self.decrypt_polynomial(c);
+
+        // Pad with zeros if the length is less than N (due to coefficients trimming in round_coefficients)
+        let mut coeffs = mb.iter();
+        core::array::from_fn(|_| coeffs.next().cloned().unwrap_or_else(Zq::I::zero))
+    }
+
+    /// Decrypts the given ciphertext and returns exactly `len` bits, i.e. the
+    /// zero padding is removed. The length is usually obtained from the header
+    /// of [Message::to_bytes](crate::Message::to_bytes) or [Message::len](crate::Message::len).
+    ///
+    /// ## Safety
+    /// `len` must be less than or equal to `N`. Use [DecryptKey::try_decrypt_exact] for
+    /// untrusted input.
+    pub fn decrypt_exact(&self, c: CipherText<Zq, N>, len: usize) -> Vec<Zq::I>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        self.try_decrypt_exact(c, len)
+            .expect("length must be at most N")
+    }
+
+    /// Decrypts the given ciphertext and returns exactly `len` bits. Returns
+    /// [Error::InvalidLength] if `len` is larger than `N`.
+    pub fn try_decrypt_exact(&self, c: CipherText<Zq, N>, len: usize) -> Result<Vec<Zq::I>, Error>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        if len > N {
+            return Err(Error::InvalidLength);
+        }
+        let mut m = self.decrypt(c);
+        m.truncate(len);
+        Ok(m)
+    }
+
+    /// Decrypts the given ciphertext into a [Message] of exactly `original_len` bits, i.e.
+    /// the zero padding is removed like [DecryptKey::decrypt_exact].
+    ///
+    /// ```
+    /// use rlwe_encryption::{standard, Message};
+    ///
+    /// let rng = &mut rand::rng();
+    /// let (ek, dk) = standard(rng);
+    /// let m = Message::new(vec![1, 0, 1]);
+    /// let c = ek.encrypt(rng, m.clone());
+    /// assert_eq!(dk.decrypt_message(c, m.len()), m);
+    /// ```
+    ///
+    /// ## Safety
+    /// `original_len` must be less than or equal to `N`, otherwise it panics.
+    pub fn decrypt_message(&self, c: CipherText<Zq, N>, original_len: usize) -> Message<Zq, N>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        Message {
+            data: self.decrypt_exact(c, original_len),
+        }
+    }
+
+    /// Decrypts the ciphertext blinded by [CipherText::blind] and removes the blinding
+    /// `mask`, i.e. returns the decryption of the original ciphertext. The result has
+    /// length `N`, and `mask` shorter than `N` is treated as padded with zeros.
+    pub fn unblind(&self, c: CipherText<Zq, N>, mask: &[Zq::I]) -> Vec<Zq::I>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let mut m = self.decrypt(c);
+        m.iter_mut().zip(mask.iter()).for_each(|(mi, ki)| {
+            *mi = if (mi.clone() + ki.clone()).is_odd() {
+                Zq::I::one()
+            } else {
+                Zq::I::zero()
+            };
+        });
+        m
+    }
+
+    /// Computes the noise `v - u * s - [q/2] m` of the ciphertext `c`, where `m` is the
+    /// decrypted (rounded) message, and returns the maximum absolute value of its
+    /// coefficients. The decryption is correct as long as the noise is less than
+    /// [decryption_margin], i.e. `Q/4`.
+    ///
+    /// The noise of a fresh ciphertext should be far below the margin. Values approaching
+    /// `Q/4` indicate that the parameters are too aggressive (or too many homomorphic
+    /// operations were applied), and decryption may fail. Above the margin, the rounding
+    /// may have already flipped a bit, so the noise is measured against the wrong message.
+    pub fn noise_magnitude(&self, c: &CipherText<Zq, N>) -> Zq::I
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let raw = self.raw_polynomial(c.clone());
+        let q_div_2_m = scale_coefficients::<Zq, N>(round_coefficients::<Zq, N>(raw.clone()));
+        modulo_coefficients::<Zq, N>(raw - q_div_2_m)
+            .iter()
+            .map(|e| e.abs())
+            .max()
+            .unwrap_or_else(Zq::I::zero)
+    }
+
+    /// Encrypts `trials` random messages with the encryption key `ek`, and collects the
+    /// residual noise `v - u * s - [q/2] m` of each ciphertext, for statistical analysis
+    /// of the noise distribution. The decryption is correct as long as all coefficients
+    /// of the noise are less than `Q/4` in absolute value.
+    pub fn noise_samples(
+        &self,
+        ek: &EncryptKey<Zq, N>,
+        rng: &mut impl Rng,
+        trials: usize,
+    ) -> Vec<Polynomial<Zq::I, N>>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        (0..trials)
+            .map(|_| {
+                let m = Message::<Zq, N>::random(rng, N);
+                let q_div_2_m =
+                    scale_coefficients::<Zq, N>(Polynomial::from_coeffs(m.data.clone()));
+                let c = ek.encrypt(rng, m);
+
+                // noise = v - u * s - [q/2] m
+                let u_s = mul_polynomials::<Zq, N>(&c.u, &self.s);
+                let v_u_s = modulo_coefficients::<Zq, N>(c.v - u_s);
+                modulo_coefficients::<Zq, N>(v_u_s - q_div_2_m)
+            })
+            .collect()
+    }
+
+    /// Computes `v - u * s` and rounds each coefficient to either 0 or 1.
+    fn decrypt_polynomial(&self, c: CipherText<Zq, N>) -> Polynomial<Zq::I, N>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        round_coefficients::<Zq, N>(self.raw_polynomial(c))
+    }
+
+    /// Computes `v - u * s`. Returns the zero polynomial if the ciphertext is malformed,
+    /// without multiplying.
+    fn raw_polynomial(&self, c: CipherText<Zq, N>) -> Polynomial<Zq::I, N>
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        if !c.is_well_formed() {
+            return Polynomial::zero();
+        }
+
+        // m = v - u * s
+        let u_s = mul_polynomials::<Zq, N>(&c.u, &self.s);
+        modulo_coefficients::<Zq, N>(c.v - u_s)
+    }
+}
+
+/// Returns the bound `Q/4` on the absolute noise (see [DecryptKey::noise_magnitude]) below
+/// which the decryption is correct.
+///
+/// ```
+/// use rlwe_encryption::{decryption_margin, StandardZq};
+///
+/// assert_eq!(decryption_margin::<StandardZq, 256>(), 832);
+/// ```
+pub fn decryption_margin<Zq: IntField, const N: usize>() -> Zq::I {
+    let four = Zq::I::one() + Zq::I::one() + Zq::I::one() + Zq::I::one();
+    Zq::Q / four
+}
+
+/// The domain separation tag for deriving the evolved key in [DecryptKey::evolve].
+const EVOLVE_DOMAIN: &[u8] = b"rlwe-encryption/evolve";
+
+/// Returns the size in bytes of a decryption key in the compact format (see
+/// [DecryptKey::to_compact]).
+///
+/// ```
+/// use rlwe_encryption::{decrypt_key_size, StandardZq};
+///
+/// assert_eq!(decrypt_key_size::<StandardZq, 256>(), 384);
+/// ```
+pub fn decrypt_key_size<Zq: IntField, const N: usize>() -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    packed_size::<Zq, N>(1)
 }
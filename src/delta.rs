@@ -0,0 +1,92 @@
+//! Defines the delta encoding between two ciphertexts.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+use poly_ring_xnp1::Polynomial;
+
+use crate::{
+    intfield::IntField,
+    polynomial::{modulo_coefficients, to_fixed_coeffs_vec},
+    CipherText, Error,
+};
+
+/// The differences of the coefficients between two ciphertexts, computed by
+/// [CipherText::diff]. Only the changed coefficients are stored, as pairs of the index and
+/// the difference modulo q, so that a receiver who already has the base ciphertext can
+/// reconstruct the other one with [CipherText::apply_diff].
+///
+/// It is small for ciphertexts which differ in a few coefficients, e.g. after
+/// [CipherText::add_plaintext] with a sparse message. Re-randomization changes almost all
+/// coefficients, so the delta can be larger than the ciphertext itself.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CipherTextDelta<Zq: IntField, const N: usize> {
+    pub(crate) u: Vec<(u32, Zq::I)>,
+    pub(crate) v: Vec<(u32, Zq::I)>,
+}
+
+impl<Zq: IntField, const N: usize> CipherTextDelta<Zq, N> {
+    /// Returns the number of changed coefficients.
+    pub fn len(&self) -> usize {
+        self.u.len() + self.v.len()
+    }
+
+    /// Returns true if the ciphertexts are identical.
+    pub fn is_empty(&self) -> bool {
+        self.u.is_empty() && self.v.is_empty()
+    }
+}
+
+impl<Zq: IntField, const N: usize> CipherText<Zq, N> {
+    /// Computes the delta from this ciphertext to `other`, such that
+    /// `self.apply_diff(&self.diff(&other))` reconstructs `other`.
+    pub fn diff(&self, other: &Self) -> CipherTextDelta<Zq, N> {
+        CipherTextDelta {
+            u: diff_polynomial::<Zq, N>(&self.u, &other.u),
+            v: diff_polynomial::<Zq, N>(&self.v, &other.v),
+        }
+    }
+
+    /// Applies the `delta` computed by [CipherText::diff] to this ciphertext. Returns
+    /// [Error::InvalidLength] if an index of the delta is not less than `N`, e.g. for a
+    /// delta deserialized from untrusted data.
+    pub fn apply_diff(&self, delta: &CipherTextDelta<Zq, N>) -> Result<Self, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        Ok(Self {
+            u: apply_polynomial::<Zq, N>(&self.u, &delta.u)?,
+            v: apply_polynomial::<Zq, N>(&self.v, &delta.v)?,
+        })
+    }
+}
+
+/// Returns the indices and the differences `b - a` of the changed coefficients.
+fn diff_polynomial<Zq: IntField, const N: usize>(
+    a: &Polynomial<Zq::I, N>,
+    b: &Polynomial<Zq::I, N>,
+) -> Vec<(u32, Zq::I)> {
+    to_fixed_coeffs_vec::<Zq, N>(a)
+        .into_iter()
+        .zip(to_fixed_coeffs_vec::<Zq, N>(b))
+        .enumerate()
+        .filter(|(_, (ai, bi))| ai != bi)
+        .map(|(i, (ai, bi))| (i as u32, Zq::modulo(&(bi - ai))))
+        .collect()
+}
+
+/// Adds the differences to the coefficients of `a` at their indices.
+fn apply_polynomial<Zq: IntField, const N: usize>(
+    a: &Polynomial<Zq::I, N>,
+    delta: &[(u32, Zq::I)],
+) -> Result<Polynomial<Zq::I, N>, Error>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let mut coeffs = to_fixed_coeffs_vec::<Zq, N>(a);
+    for (i, d) in delta {
+        let ci = coeffs.get_mut(*i as usize).ok_or(Error::InvalidLength)?;
+        *ci = &*ci + d;
+    }
+    Ok(modulo_coefficients::<Zq, N>(Polynomial::new(coeffs)))
+}
@@ -0,0 +1,177 @@
+//! Defines the [DynField] whose parameters are given at runtime.
+
+use alloc::vec::Vec;
+use poly_ring_xnp1::Polynomial;
+use rand::Rng;
+
+use crate::{params::check_parameter_values, IntField, ParamError, StandardZq};
+
+/// A field with the modulus `q` and the boundary `b` of the small polynomials given at
+/// runtime, instead of the constants of an [IntField](crate::IntField). The length `N` of
+/// the polynomials is still a const generic.
+///
+/// It is intended for experiments, e.g. sweeping the parameters in a loop without defining
+/// a type for each of them. Only the basic key generation, encryption and decryption are
+/// provided. The default is the parameters of [StandardZq].
+///
+/// ```
+/// use rlwe_encryption::DynField;
+///
+/// let rng = &mut rand::rng();
+/// let field = DynField::new(7681, 1);
+/// assert!(field.check_parameters::<256>().is_ok());
+///
+/// let (ek, dk) = field.key_gen::<256>(rng);
+/// let c = ek.encrypt(rng, &[1, 0, 1]);
+/// assert_eq!(dk.decrypt(&c)[..3], [1, 0, 1]);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynField {
+    /// The modulus.
+    pub q: i64,
+    /// The boundary of the coefficients of the small polynomials.
+    pub b: i64,
+}
+
+impl Default for DynField {
+    fn default() -> Self {
+        Self::new(StandardZq::Q as i64, StandardZq::B as i64)
+    }
+}
+
+impl DynField {
+    /// Creates a field with the modulus `q` and the boundary `b`. The parameters are not
+    /// checked, see [DynField::check_parameters].
+    pub fn new(q: i64, b: i64) -> Self {
+        Self { q, b }
+    }
+
+    /// Checks the parameters with the length `N` like
+    /// [check_parameters](crate::check_parameters).
+    pub fn check_parameters<const N: usize>(&self) -> Result<(), ParamError> {
+        check_parameter_values(N, self.q as i128, self.b as i128)
+    }
+
+    /// Reduces `x` modulo `q` into the symmetric representation `[-(q-1)/2, q/2]`.
+    pub fn modulo(&self, x: i64) -> i64 {
+        let a = x.rem_euclid(self.q);
+        if a > self.q / 2 {
+            a - self.q
+        } else {
+            a
+        }
+    }
+
+    /// Generates a pair of encryption and decryption keys, like
+    /// [key_gen](crate::key_gen).
+    ///
+    /// ## Safety
+    /// The parameters should pass [DynField::check_parameters], otherwise the decryption
+    /// may be incorrect. The coefficients must not overflow `i64`, i.e. `N * q * b` must
+    /// be well below `2^63`.
+    pub fn key_gen<const N: usize>(
+        &self,
+        rng: &mut impl Rng,
+    ) -> (DynFieldEncryptKey<N>, DynFieldDecryptKey<N>) {
+        let a = self.rand_polynomial(rng);
+        let s = self.small_polynomial(rng);
+        let e = self.small_polynomial(rng);
+        // t = a * s + e
+        let t = self.reduce(self.reduce(a.clone() * s.clone()) + e);
+        (
+            DynFieldEncryptKey { field: *self, a, t },
+            DynFieldDecryptKey { field: *self, s },
+        )
+    }
+
+    fn reduce<const N: usize>(&self, mut p: Polynomial<i64, N>) -> Polynomial<i64, N> {
+        p.coeffs_mut(|c| *c = self.modulo(*c));
+        p
+    }
+
+    fn rand_polynomial<const N: usize>(&self, rng: &mut impl Rng) -> Polynomial<i64, N> {
+        let upper = self.q / 2;
+        Polynomial::new((0..N).map(|_| rng.random_range(-upper..=upper)).collect())
+    }
+
+    fn small_polynomial<const N: usize>(&self, rng: &mut impl Rng) -> Polynomial<i64, N> {
+        Polynomial::new((0..N).map(|_| rng.random_range(-self.b..=self.b)).collect())
+    }
+
+    /// Returns the closest integer to `q/2`, with ties broken upwards.
+    fn half_q(&self) -> i64 {
+        (self.q + 1) / 2
+    }
+}
+
+/// The encryption key of a [DynField], created by [DynField::key_gen].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynFieldEncryptKey<const N: usize> {
+    field: DynField,
+    a: Polynomial<i64, N>,
+    t: Polynomial<i64, N>,
+}
+
+impl<const N: usize> DynFieldEncryptKey<N> {
+    /// Returns the field of the key.
+    pub fn field(&self) -> DynField {
+        self.field
+    }
+
+    /// Encrypts the binary message `m`, like [EncryptKey::encrypt](crate::EncryptKey::encrypt).
+    ///
+    /// ## Safety
+    /// The message must be binary with length at most `N`.
+    pub fn encrypt(&self, rng: &mut impl Rng, m: &[i64]) -> DynFieldCipherText<N> {
+        let field = &self.field;
+        let r = field.small_polynomial(rng);
+        let e2 = field.small_polynomial(rng);
+        let e3 = field.small_polynomial(rng);
+        let scaled_m = Polynomial::new(m.iter().map(|b| b * field.half_q()).collect::<Vec<_>>());
+
+        // u = a * r + e2, v = t * r + e3 + [q/2] m
+        let u = field.reduce(field.reduce(self.a.clone() * r.clone()) + e2);
+        let v = field.reduce(field.reduce(self.t.clone() * r) + e3 + scaled_m);
+        DynFieldCipherText { u, v }
+    }
+}
+
+/// The decryption key of a [DynField], created by [DynField::key_gen].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynFieldDecryptKey<const N: usize> {
+    field: DynField,
+    s: Polynomial<i64, N>,
+}
+
+impl<const N: usize> DynFieldDecryptKey<N> {
+    /// Returns the field of the key.
+    pub fn field(&self) -> DynField {
+        self.field
+    }
+
+    /// Decrypts the ciphertext into `N` bits, like
+    /// [DecryptKey::decrypt](crate::DecryptKey::decrypt).
+    pub fn decrypt(&self, c: &DynFieldCipherText<N>) -> Vec<i64> {
+        let field = &self.field;
+        // v - u * s = [q/2] m + noise
+        let mb = field.reduce(c.v.clone() - field.reduce(c.u.clone() * self.s.clone()));
+        let threshold = field.half_q() / 2;
+        let mut m = mb
+            .iter()
+            .map(|c| (c.abs() > threshold) as i64)
+            .collect::<Vec<_>>();
+        m.resize(N, 0);
+        m
+    }
+}
+
+/// The ciphertext of a [DynField], created by [DynFieldEncryptKey::encrypt].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynFieldCipherText<const N: usize> {
+    u: Polynomial<i64, N>,
+    v: Polynomial<i64, N>,
+}
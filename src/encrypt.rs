@@ -1,14 +1,22 @@
 //! Defines the encryption key and the encryption method.
 
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Mul, Sub};
+use num::{FromPrimitive, One, Signed, ToPrimitive, Zero};
 use poly_ring_xnp1::Polynomial;
 use rand::Rng;
-use std::ops::{Add, Mul, Sub};
 
 use crate::{
     ciphertext::CipherText,
     intfield::IntField,
-    polynomial::{modulo_coefficients, scale_coefficients, small_polynomial},
-    Message,
+    message::validate_message,
+    polynomial::{
+        closest_integer_div_two, from_fixed_width_bytes, modulo_coefficients, mul_polynomials,
+        pack_coefficients, packed_size, scale_coefficients, small_polynomial, to_fixed_coeffs_vec,
+        to_fixed_width_bytes, unpack_coefficients,
+    },
+    xof::XofRng,
+    DecodeError, EncryptError, Error, KeyError, Message, PackError,
 };
 
 /// The encryption key created by the key generation method.
@@ -25,33 +33,568 @@ pub struct EncryptKey<Zq: IntField, const N: usize> {
 }
 
 impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Creates a key from the coefficients of its polynomials `a` and `t`, e.g. loaded from
+    /// a custom storage format. The coefficients are reduced modulo q. Returns
+    /// [KeyError::InvalidLength] if either does not have exactly `N` coefficients.
+    pub fn from_parts(a: Vec<Zq::I>, t: Vec<Zq::I>) -> Result<Self, KeyError> {
+        if let Some(p) = [&a, &t].into_iter().find(|p| p.len() != N) {
+            return Err(KeyError::InvalidLength {
+                expected: N,
+                actual: p.len(),
+            });
+        }
+        Ok(Self {
+            a: modulo_coefficients::<Zq, N>(Polynomial::new(a)),
+            t: modulo_coefficients::<Zq, N>(Polynomial::new(t)),
+        })
+    }
+
+    /// Returns the `N` coefficients of the polynomials `a` and `t`, the inverse of
+    /// [EncryptKey::from_parts].
+    ///
+    /// They are returned by value, since [Polynomial] trims the trailing zero coefficients
+    /// and does not expose its coefficients as a slice.
+    pub fn parts(&self) -> (Vec<Zq::I>, Vec<Zq::I>) {
+        (
+            to_fixed_coeffs_vec::<Zq, N>(&self.a),
+            to_fixed_coeffs_vec::<Zq, N>(&self.t),
+        )
+    }
+
+    /// Returns true if both keys are generated from the same public polynomial `a`,
+    /// i.e. they share the same common reference string.
+    pub fn same_crs(&self, other: &Self) -> bool {
+        self.a == other.a
+    }
+
+    /// Serializes the key into the compact bit-packed format, in which each coefficient
+    /// is stored with `ceil(log2(Q))` bits.
+    pub fn to_compact(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        pack_coefficients::<Zq, N>(&[&self.a, &self.t])
+    }
+
+    /// Deserializes a key from the compact bit-packed format produced by
+    /// [EncryptKey::to_compact].
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        let mut polys = unpack_coefficients::<Zq, N>(bytes, 2).ok_or(Error::InvalidKeyBytes)?;
+        let t = polys.pop().unwrap();
+        let a = polys.pop().unwrap();
+        Ok(Self { a, t })
+    }
+
+    /// Serializes the key into the fixed-width format: the `N` coefficients of `a`
+    /// followed by the `N` coefficients of `t`, each in the symmetric representation
+    /// `[-(Q-1)/2, Q/2]` as a signed (two's complement) little-endian integer of
+    /// `size_of::<I>()` bytes. The total length is `2 * N * size_of::<I>()` bytes, without
+    /// any header. See [CipherText::to_bytes].
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        to_fixed_width_bytes::<Zq, N>(&[&self.a, &self.t])
+    }
+
+    /// Deserializes a key from the fixed-width format produced by [EncryptKey::to_bytes].
+    /// Returns [DecodeError::WrongLength] if the length is not exactly
+    /// `2 * N * size_of::<I>()`, or [DecodeError::InvalidCoefficient] if a coefficient is
+    /// not in the symmetric representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        Zq::I: FromPrimitive,
+    {
+        let mut polys = from_fixed_width_bytes::<Zq, N>(bytes, 2)?;
+        let t = polys.pop().unwrap();
+        let a = polys.pop().unwrap();
+        Ok(Self { a, t })
+    }
+
     /// Encrypts a message `m` using the public key.
+    ///
+    /// With the `checked` feature, it panics if the message is longer than `N` or not
+    /// binary, e.g. deserialized from untrusted data. By default, the checks are skipped
+    /// for performance. Use [EncryptKey::try_encrypt] for untrusted input.
     pub fn encrypt(&self, rng: &mut impl Rng, m: Message<Zq, N>) -> CipherText<Zq, N>
     where
         for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
     {
-        let r = small_polynomial::<Zq, N>(rng);
-        let e2 = small_polynomial::<Zq, N>(rng);
-        let e3 = small_polynomial::<Zq, N>(rng);
+        self.encrypt_with_opening(rng, m).0
+    }
 
-        // u = a * r + e2
-        let u = {
-            let a_r = modulo_coefficients::<Zq, N>(self.a.clone() * r.clone());
-            modulo_coefficients::<Zq, N>(a_r + e2)
+    /// Encrypts the message `m` after checking that it is binary with length at most `N`,
+    /// the same validation as [Message::try_new]. Returns [EncryptError::TooLong] or
+    /// [EncryptError::NonBinary] (with the first offending element) for an invalid message.
+    ///
+    /// Use [EncryptKey::encrypt] to skip the checks for an already validated [Message].
+    ///
+    /// ```
+    /// use rlwe_encryption::{key_gen, EncryptError, StandardZq};
+    ///
+    /// let rng = &mut rand::rng();
+    /// let (ek, _) = key_gen::<StandardZq, 256>(rng);
+    /// assert!(ek.try_encrypt(rng, vec![0, 1, 1]).is_ok());
+    /// assert_eq!(
+    ///     ek.try_encrypt(rng, vec![0, 1, 2]).unwrap_err(),
+    ///     EncryptError::NonBinary { index: 2, value: 2 }
+    /// );
+    /// ```
+    pub fn try_encrypt(
+        &self,
+        rng: &mut impl Rng,
+        m: Vec<Zq::I>,
+    ) -> Result<CipherText<Zq, N>, EncryptError>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        validate_message::<Zq, N>(&m)?;
+        Ok(self.encrypt(rng, Message { data: m }))
+    }
+
+    /// Encrypts the field elements `coeffs` in `[0, t)` with the plaintext modulus `t`,
+    /// bypassing the binary encoding of [Message]. Each coefficient is scaled by
+    /// `round(q/t)` instead of `[q/2]`. Decrypt it with [DecryptKey::decrypt_field_elements]
+    /// and the same `t`.
+    ///
+    /// The decryption is correct as long as the noise is less than `q/(2t)`, so the margin
+    /// shrinks as `t` grows. Returns [Error::InvalidLength] if there are more than `N`
+    /// coefficients, and [Error::InvalidMessage] if `t` is not in `[2, Q)` or a coefficient
+    /// is not in `[0, t)`.
+    ///
+    /// [DecryptKey::decrypt_field_elements]: crate::DecryptKey::decrypt_field_elements
+    pub fn encrypt_field_elements(
+        &self,
+        rng: &mut impl Rng,
+        coeffs: Vec<Zq::I>,
+        t: Zq::I,
+    ) -> Result<CipherText<Zq, N>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        if coeffs.len() > N {
+            return Err(Error::InvalidLength);
+        }
+        let two = Zq::I::one() + Zq::I::one();
+        if t < two || t >= Zq::Q || coeffs.iter().any(|c| c.is_negative() || c >= &t) {
+            return Err(Error::InvalidMessage);
+        }
+
+        // round(q/t) = floor((2q + t) / 2t)
+        let delta = (two.clone() * Zq::Q + t.clone()) / (two * t);
+        let mut scaled_m = Polynomial::<_, N>::from_coeffs(coeffs);
+        scaled_m.coeffs_mut(|c| *c = Zq::modulo(&(c.clone() * delta.clone())));
+
+        let opening = Opening {
+            r: small_polynomial::<Zq, N>(rng),
+            e2: small_polynomial::<Zq, N>(rng),
+            e3: small_polynomial::<Zq, N>(rng),
+        };
+        Ok(self.encrypt_scaled(&opening, scaled_m))
+    }
+
+    /// Encrypts the balanced ternary message `m` with symbols in `{-1, 0, 1}`, by scaling
+    /// the symbols `0`, `1` and `-1` to `0`, `[q/3]` and `2 * [q/3]` respectively, i.e. as
+    /// field elements in `[0, 3)` (see [EncryptKey::encrypt_field_elements] with `t = 3`).
+    /// Decrypt it with [DecryptKey::decrypt_ternary].
+    ///
+    /// The decryption is correct only if the noise is less than `q/6` instead of `q/4`, so
+    /// the parameters must satisfy the stricter condition `2N * B^2 + B < Q/6`, e.g. a
+    /// modulus of about 1.5 times the one for binary messages. Returns [Error::InvalidLength]
+    /// if there are more than `N` symbols, and [Error::InvalidMessage] if a symbol is not
+    /// in `{-1, 0, 1}`.
+    ///
+    /// [DecryptKey::decrypt_ternary]: crate::DecryptKey::decrypt_ternary
+    pub fn encrypt_ternary(
+        &self,
+        rng: &mut impl Rng,
+        m: Vec<Zq::I>,
+    ) -> Result<CipherText<Zq, N>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let one = Zq::I::one();
+        let two = one.clone() + one.clone();
+        let elements = m
+            .into_iter()
+            .map(|mi| {
+                if mi.is_zero() || mi.is_one() {
+                    Ok(mi)
+                } else if mi == -one.clone() {
+                    Ok(two.clone())
+                } else {
+                    Err(Error::InvalidMessage)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.encrypt_field_elements(rng, elements, two + one)
+    }
+
+    /// Encrypts the binary message `m` borrowed from a slice, e.g. a part of a larger buffer,
+    /// without requiring an owned [Message]. The message is scaled into the polynomial
+    /// directly, which saves the allocation of a copy of the message.
+    ///
+    /// ## Safety
+    /// Like [Message::new], `m` must be binary with length at most `N`. It is checked (and
+    /// panics if invalid) only with the `checked` feature.
+    pub fn encrypt_slice(&self, rng: &mut impl Rng, m: &[Zq::I]) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        #[cfg(feature = "checked")]
+        check_message::<Zq, N>(m);
+
+        let q_div_2 = closest_integer_div_two(Zq::Q);
+        let q_div_2_m = Polynomial::<_, N>::from_coeffs(m.iter().map(|mi| &q_div_2 * mi).collect());
+
+        let opening = Opening {
+            r: small_polynomial::<Zq, N>(rng),
+            e2: small_polynomial::<Zq, N>(rng),
+            e3: small_polynomial::<Zq, N>(rng),
         };
+        self.encrypt_scaled(&opening, q_div_2_m)
+    }
+
+    /// Packs several short binary messages into one ciphertext, by concatenating them into
+    /// the `N` coefficient slots. Decrypt it with [DecryptKey::decrypt_packed] and the
+    /// lengths of the messages. All packed bits share the noise budget of one ciphertext,
+    /// e.g. a homomorphic operation on the ciphertext applies to all of them.
+    ///
+    /// Returns [PackError::TooLong] if the total length exceeds `N`, and
+    /// [PackError::NonBinary] (with the first offending element) if a message is not binary.
+    ///
+    /// [DecryptKey::decrypt_packed]: crate::DecryptKey::decrypt_packed
+    pub fn encrypt_packed(
+        &self,
+        rng: &mut impl Rng,
+        messages: &[Vec<Zq::I>],
+    ) -> Result<CipherText<Zq, N>, PackError>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let total = messages.iter().map(Vec::len).sum::<usize>();
+        if total > N {
+            return Err(PackError::TooLong { total, n: N });
+        }
+        for (message, m) in messages.iter().enumerate() {
+            if let Err(EncryptError::NonBinary { index, value }) = validate_message::<Zq, N>(m) {
+                return Err(PackError::NonBinary {
+                    message,
+                    index,
+                    value,
+                });
+            }
+        }
+        Ok(self.encrypt(
+            rng,
+            Message {
+                data: messages.concat(),
+            },
+        ))
+    }
+
+    /// Encrypts the one-hot vector of length `len`, i.e. the message which has a single 1
+    /// at position `index` and zeros elsewhere.
+    ///
+    /// ## Safety
+    /// `index` must be less than `len`, and `len` must be less than or equal to `N`.
+    /// Use [EncryptKey::try_encrypt_one_hot] for untrusted input.
+    pub fn encrypt_one_hot(&self, rng: &mut impl Rng, index: usize, len: usize) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.try_encrypt_one_hot(rng, index, len)
+            .expect("index must be less than length, and length must be at most N")
+    }
+
+    /// Encrypts the one-hot vector of length `len` with a single 1 at position `index`.
+    /// Returns [Error::InvalidLength] unless `index < len <= N`.
+    pub fn try_encrypt_one_hot(
+        &self,
+        rng: &mut impl Rng,
+        index: usize,
+        len: usize,
+    ) -> Result<CipherText<Zq, N>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        if index >= len || len > N {
+            return Err(Error::InvalidLength);
+        }
+        let mut data = vec![Zq::I::zero(); len];
+        data[index] = Zq::I::one();
+        Ok(self.encrypt(rng, Message { data }))
+    }
+
+    /// Encrypts a message `m` using the public key, and returns the ciphertext together
+    /// with the [Opening], i.e. the randomness used in the encryption.
+    ///
+    /// The opening allows anyone holding the message to check that the ciphertext
+    /// encrypts it (see [EncryptKey::verify_opening]). It must be kept secret otherwise,
+    /// as it reveals the message to anyone holding the ciphertext.
+    pub fn encrypt_with_opening(
+        &self,
+        rng: &mut impl Rng,
+        m: Message<Zq, N>,
+    ) -> (CipherText<Zq, N>, Opening<Zq, N>)
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let opening = Opening {
+            r: small_polynomial::<Zq, N>(rng),
+            e2: small_polynomial::<Zq, N>(rng),
+            e3: small_polynomial::<Zq, N>(rng),
+        };
+
+        (self.encrypt_with(&opening, m), opening)
+    }
+
+    /// Re-randomizes the ciphertext `c` by adding a fresh encryption of zero, i.e.
+    /// `u + a * r' + e2'` and `v + t * r' + e3'`. The result decrypts to the same message,
+    /// but cannot be linked to `c` without the decryption key, e.g. to forward a ciphertext
+    /// in a mix network.
+    ///
+    /// The noise of the fresh encryption is added to the noise of `c`, so it costs as much
+    /// of the noise budget as a homomorphic addition (see
+    /// [max_homomorphic_additions](crate::max_homomorphic_additions)). A ciphertext
+    /// re-randomized many times eventually fails to decrypt.
+    ///
+    /// ```
+    /// use rlwe_encryption::{standard, Message};
+    ///
+    /// let rng = &mut rand::rng();
+    /// let (ek, dk) = standard(rng);
+    /// let c = ek.encrypt(rng, Message::new(vec![1, 0, 1]));
+    /// let c2 = ek.rerandomize(rng, c.clone());
+    /// assert_ne!(c, c2);
+    /// assert_eq!(dk.decrypt(c), dk.decrypt(c2));
+    /// ```
+    pub fn rerandomize(&self, rng: &mut impl Rng, c: CipherText<Zq, N>) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let opening = Opening {
+            r: small_polynomial::<Zq, N>(rng),
+            e2: small_polynomial::<Zq, N>(rng),
+            e3: small_polynomial::<Zq, N>(rng),
+        };
+        let zero = self.encrypt_scaled(&opening, Polynomial::zero());
+        c.add_ciphertext(&zero)
+    }
+
+    /// Encrypts the messages `msgs` with a single randomness `r` shared across the batch, and
+    /// fresh `e2` and `e3` for each message. It is for research into randomness reuse (e.g.
+    /// measuring the tradeoff against [EncryptKey::encrypt]), **not for production**.
+    ///
+    /// ## Security warning
+    /// This is **insecure**. The ciphertexts share `a * r`, so their `u` differ only by the
+    /// small `e2`, which links them together, and `v_i - v_j = [q/2] (m_i - m_j)` plus a small
+    /// noise, which reveals the XOR of any two messages in the batch without any key.
+    pub fn encrypt_batch_shared_r(
+        &self,
+        rng: &mut impl Rng,
+        msgs: Vec<Message<Zq, N>>,
+    ) -> Vec<CipherText<Zq, N>>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let r = small_polynomial::<Zq, N>(rng);
+        msgs.into_iter()
+            .map(|m| {
+                let opening = Opening {
+                    r: r.clone(),
+                    e2: small_polynomial::<Zq, N>(rng),
+                    e3: small_polynomial::<Zq, N>(rng),
+                };
+                self.encrypt_with(&opening, m)
+            })
+            .collect()
+    }
+
+    /// Encrypts a message `m` with the noise bounded by `max_noise`, by resampling the
+    /// randomness `(r, e2, e3)` until the worst-case bound of the decryption noise
+    /// `e * r + e3 - e2 * s` is at most `max_noise`. The bound `B * (|r|_1 + |e2|_1) + |e3|_inf`
+    /// holds whatever the secret `s` and the key noise `e`, which the encryptor does not know.
+    ///
+    /// A tighter noise leaves more room for homomorphic operations, at the cost of encryption
+    /// time. Returns [Error::RetryLimitExceeded] after [MAX_NOISE_RETRIES] attempts, e.g. if
+    /// `max_noise` is too small to be reached with reasonable probability. Please note that
+    /// the rejection leaks the bound on the randomness, which is public information anyway.
+    pub fn encrypt_bounded_noise(
+        &self,
+        rng: &mut impl Rng,
+        m: Message<Zq, N>,
+        max_noise: &Zq::I,
+    ) -> Result<CipherText<Zq, N>, Error>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let l1_norm =
+            |p: &Polynomial<Zq::I, N>| p.iter().fold(Zq::I::zero(), |acc, c| acc + c.abs());
+        let linf_norm =
+            |p: &Polynomial<Zq::I, N>| p.iter().map(|c| c.abs()).max().unwrap_or_else(Zq::I::zero);
+
+        for _ in 0..MAX_NOISE_RETRIES {
+            let opening = Opening {
+                r: small_polynomial::<Zq, N>(rng),
+                e2: small_polynomial::<Zq, N>(rng),
+                e3: small_polynomial::<Zq, N>(rng),
+            };
+            let bound =
+                Zq::B * (l1_norm(&opening.r) + l1_norm(&opening.e2)) + linf_norm(&opening.e3);
+            if &bound <= max_noise {
+                return Ok(self.encrypt_with(&opening, m));
+            }
+        }
+        Err(Error::RetryLimitExceeded)
+    }
+
+    /// Encrypts a message `m` deterministically, deriving all the randomness from the
+    /// SHAKE128 hash of `transcript`. An auditor holding the transcript and the message
+    /// can recompute the ciphertext and compare it with the published one.
+    ///
+    /// Anyone holding the transcript can also recover the message from the ciphertext,
+    /// so the transcript must be as confidential as the message, and must not be reused
+    /// for different messages.
+    pub fn encrypt_auditable(&self, m: Message<Zq, N>, transcript: &[u8]) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        self.encrypt(&mut XofRng::new(AUDITABLE_DOMAIN, transcript), m)
+    }
+
+    /// Checks whether the ciphertext `c` is the encryption of the message `m` under the
+    /// randomness `opening` returned by [EncryptKey::encrypt_with_opening].
+    pub fn verify_opening(
+        &self,
+        c: &CipherText<Zq, N>,
+        m: Message<Zq, N>,
+        opening: &Opening<Zq, N>,
+    ) -> bool
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let small = |p: &Polynomial<Zq::I, N>| p.iter().all(|c| c.abs() <= Zq::B);
+        if !(small(&opening.r) && small(&opening.e2) && small(&opening.e3)) {
+            return false;
+        }
+
+        let expected = self.encrypt_with(opening, m);
+        expected.u == c.u && expected.v == c.v
+    }
+
+    /// Encrypts a message `m` deterministically using the given randomness.
+    pub(crate) fn encrypt_with(
+        &self,
+        opening: &Opening<Zq, N>,
+        m: Message<Zq, N>,
+    ) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        #[cfg(feature = "checked")]
+        check_message::<Zq, N>(&m.data);
 
         let q_div_2_m = {
             let tmp = Polynomial::<_, N>::from_coeffs(m.data());
             scale_coefficients::<Zq, N>(tmp) // = [q/2] m
         };
+        self.encrypt_scaled(opening, q_div_2_m)
+    }
+
+    /// Encrypts the plaintext polynomial `scaled_m` which is already scaled to the
+    /// ciphertext modulus, i.e. computes `u = a * r + e2` and `v = t * r + e3 + scaled_m`.
+    fn encrypt_scaled(
+        &self,
+        opening: &Opening<Zq, N>,
+        scaled_m: Polynomial<Zq::I, N>,
+    ) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let r = &opening.r;
+
+        // u = a * r + e2
+        let u = {
+            let a_r = mul_polynomials::<Zq, N>(&self.a, r);
+            modulo_coefficients::<Zq, N>(a_r + opening.e2.clone())
+        };
 
-        // v = t * r + e3 + [q/2] m
+        // v = t * r + e3 + scaled_m
         let v = {
-            let t_r = modulo_coefficients::<Zq, N>(self.t.clone() * r.clone());
-            let t_r_e3 = modulo_coefficients::<Zq, N>(t_r + e3);
-            modulo_coefficients::<Zq, N>(t_r_e3 + q_div_2_m)
+            let t_r = mul_polynomials::<Zq, N>(&self.t, r);
+            let t_r_e3 = modulo_coefficients::<Zq, N>(t_r + opening.e3.clone());
+            modulo_coefficients::<Zq, N>(t_r_e3 + scaled_m)
         };
 
         CipherText { u, v }
     }
 }
+
+/// Encrypts the message `m` under each of the encryption keys `keys` with independent
+/// randomness, so that any of the corresponding decryption keys can decrypt it, e.g. for
+/// redundant storage. Unlike broadcast, the keys need not share the same public polynomial
+/// `a`. The `i`-th ciphertext is under the `i`-th key.
+pub fn encrypt_redundant<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+    keys: &[EncryptKey<Zq, N>],
+    m: Message<Zq, N>,
+) -> Vec<CipherText<Zq, N>>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    keys.iter()
+        .map(|ek| {
+            ek.encrypt(
+                rng,
+                Message {
+                    data: m.data.clone(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Asserts that the message is binary with length at most `N`.
+#[cfg(feature = "checked")]
+fn check_message<Zq: IntField, const N: usize>(m: &[Zq::I]) {
+    match validate_message::<Zq, N>(m) {
+        Err(EncryptError::TooLong { .. }) => panic!("message length must be at most N"),
+        Err(EncryptError::NonBinary { .. }) => panic!("message must be binary"),
+        Ok(()) => {}
+    }
+}
+
+/// The maximum number of attempts of [EncryptKey::encrypt_bounded_noise].
+pub const MAX_NOISE_RETRIES: usize = 1000;
+
+/// The domain separation tag for deriving the randomness of [EncryptKey::encrypt_auditable].
+const AUDITABLE_DOMAIN: &[u8] = b"rlwe-encryption/auditable";
+
+/// The randomness `(r, e2, e3)` used to encrypt a message, returned by
+/// [EncryptKey::encrypt_with_opening].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opening<Zq: IntField, const N: usize> {
+    pub(crate) r: Polynomial<Zq::I, N>,
+    pub(crate) e2: Polynomial<Zq::I, N>,
+    pub(crate) e3: Polynomial<Zq::I, N>,
+}
+
+/// Returns the size in bytes of an encryption key in the compact format (see
+/// [EncryptKey::to_compact]).
+///
+/// ```
+/// use rlwe_encryption::{encrypt_key_size, StandardZq};
+///
+/// assert_eq!(encrypt_key_size::<StandardZq, 256>(), 768);
+/// ```
+pub fn encrypt_key_size<Zq: IntField, const N: usize>() -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    packed_size::<Zq, N>(2)
+}
@@ -0,0 +1,73 @@
+//! Defines the CipherTextEnvelope struct which bundles a ciphertext with metadata.
+
+use alloc::vec::Vec;
+use num::{FromPrimitive, ToPrimitive};
+
+use crate::{ciphertext_size, CipherText, Error, IntField};
+
+/// The number of bytes used by the metadata length header in [CipherTextEnvelope::to_bytes].
+const METADATA_HEADER_SIZE: usize = 4;
+
+/// A ciphertext together with arbitrary application metadata (e.g. sender id, timestamp
+/// or message length), so that they are serialized together.
+///
+/// The metadata is not encrypted nor authenticated.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct CipherTextEnvelope<Zq: IntField, const N: usize> {
+    /// The ciphertext.
+    pub ciphertext: CipherText<Zq, N>,
+    /// The metadata associated with the ciphertext.
+    pub metadata: Vec<u8>,
+}
+
+impl<Zq: IntField, const N: usize> CipherTextEnvelope<Zq, N> {
+    /// Creates an envelope of the ciphertext with the metadata.
+    pub fn new(ciphertext: CipherText<Zq, N>, metadata: Vec<u8>) -> Self {
+        Self {
+            ciphertext,
+            metadata,
+        }
+    }
+
+    /// Serializes the envelope into bytes. The output begins with a 4-byte little-endian
+    /// length header of the metadata, followed by the metadata and the ciphertext in the
+    /// compact format (see [CipherText::to_compact]).
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Zq::I: ToPrimitive,
+    {
+        let mut bytes = Vec::with_capacity(
+            METADATA_HEADER_SIZE + self.metadata.len() + ciphertext_size::<Zq, N>(),
+        );
+        bytes.extend_from_slice(&(self.metadata.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.metadata);
+        bytes.extend_from_slice(&self.ciphertext.to_compact());
+        bytes
+    }
+
+    /// Deserializes an envelope from bytes produced by [CipherTextEnvelope::to_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+    {
+        if bytes.len() < METADATA_HEADER_SIZE {
+            return Err(Error::InvalidCipherTextBytes);
+        }
+        let (header, rest) = bytes.split_at(METADATA_HEADER_SIZE);
+        let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+        if rest.len() < len {
+            return Err(Error::InvalidCipherTextBytes);
+        }
+        let (metadata, ciphertext) = rest.split_at(len);
+
+        Ok(Self {
+            ciphertext: CipherText::from_compact(ciphertext)?,
+            metadata: metadata.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,258 @@
+//! Defines the error type returned by the fallible methods in this library.
+
+use alloc::string::String;
+use core::fmt;
+
+/// Errors that can occur when using this library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The message is not binary, or its length is larger than `N`.
+    InvalidMessage,
+    /// A length or index argument is out of range, e.g. larger than `N`.
+    InvalidLength,
+    /// The bytes cannot be decoded into a message, e.g. the length header is
+    /// missing, the declared length exceeds `N`, or the payload is truncated.
+    InvalidMessageBytes,
+    /// The bytes cannot be decoded into a ciphertext, e.g. the length does not
+    /// match `N` and `Q`, or a coefficient is out of range.
+    InvalidCipherTextBytes,
+    /// The bytes cannot be decoded into a key, e.g. the length does not match `N`
+    /// and `Q`, or a coefficient is out of range.
+    InvalidKeyBytes,
+    /// The seed for deterministic key generation has obviously low entropy, e.g.
+    /// all bytes are identical.
+    LowEntropySeed,
+    /// The JSON string cannot be deserialized.
+    InvalidJson,
+    /// The parameters are invalid, e.g. `N` is not a power of 2 or `Q` is not a prime.
+    InvalidParams,
+    /// The ciphertext is malformed, e.g. a polynomial has more than `N` coefficients.
+    InvalidCipherText,
+    /// The rejection sampling did not succeed within the retry limit.
+    RetryLimitExceeded,
+    /// The parameters `N`, `Q` and `B` do not work together. The message tells which
+    /// condition fails and suggests a fix.
+    MisconfiguredParams(String),
+    /// A chunk of a chunked message is missing, or the chunks disagree on the total.
+    IncompleteChunks,
+    /// A partial decryption does not verify against the commitment to the share of the
+    /// party, e.g. it is corrupted or computed with another share.
+    InvalidPartialDecryption {
+        /// The index of the party whose partial decryption is rejected.
+        index: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidMessage => write!(f, "invalid message"),
+            Error::InvalidLength => write!(f, "invalid length"),
+            Error::InvalidMessageBytes => write!(f, "invalid message bytes"),
+            Error::InvalidCipherTextBytes => write!(f, "invalid ciphertext bytes"),
+            Error::InvalidKeyBytes => write!(f, "invalid key bytes"),
+            Error::LowEntropySeed => write!(f, "seed has low entropy"),
+            Error::InvalidJson => write!(f, "invalid json"),
+            Error::InvalidParams => write!(f, "invalid parameters"),
+            Error::InvalidCipherText => write!(f, "invalid ciphertext"),
+            Error::RetryLimitExceeded => write!(f, "retry limit exceeded"),
+            Error::MisconfiguredParams(msg) => write!(f, "misconfigured parameters: {msg}"),
+            Error::IncompleteChunks => write!(f, "incomplete chunks"),
+            Error::InvalidPartialDecryption { index } => {
+                write!(f, "invalid partial decryption at index {index}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Errors returned by [EncryptKey::from_parts](crate::EncryptKey::from_parts) and
+/// [DecryptKey::from_parts](crate::DecryptKey::from_parts) for invalid coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// A polynomial of the key does not have exactly `N` coefficients.
+    InvalidLength {
+        /// The number of coefficients of a polynomial, i.e. `N`.
+        expected: usize,
+        /// The number of coefficients given.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::InvalidLength { expected, actual } => write!(
+                f,
+                "invalid key length: expected {expected} coefficients, got {actual}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyError {}
+
+/// Errors returned by [check_parameters](crate::check_parameters) and
+/// [try_key_gen](crate::try_key_gen) when the parameters of a field do not work together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// `N` is not a power of 2.
+    NotPowerOfTwo {
+        /// The length of the polynomials.
+        n: usize,
+    },
+    /// The modulus `Q` is not positive.
+    NonPositiveModulus {
+        /// The modulus.
+        q: i128,
+    },
+    /// The boundary `B` of the small polynomials is not positive.
+    NonPositiveBound {
+        /// The boundary of the coefficients of the small polynomials.
+        b: i128,
+    },
+    /// The condition `2N * B^2 + B < Q/4` does not hold, i.e. the decryption can fail.
+    ParametersTooSmall {
+        /// The left-hand side `2N * B^2 + B`.
+        lhs: i128,
+        /// The right-hand side `Q/4`, rounded down.
+        rhs: i128,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::NotPowerOfTwo { n } => write!(f, "N={n} is not a power of 2"),
+            ParamError::NonPositiveModulus { q } => write!(f, "Q={q} is not positive"),
+            ParamError::NonPositiveBound { b } => write!(f, "B={b} is not positive"),
+            ParamError::ParametersTooSmall { lhs, rhs } => write!(
+                f,
+                "parameters too small: 2N * B^2 + B = {lhs} is not less than Q/4 = {rhs}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParamError {}
+
+/// Errors returned by `from_bytes` of [CipherText](crate::CipherText),
+/// [EncryptKey](crate::EncryptKey) and [DecryptKey](crate::DecryptKey) when the bytes are
+/// not in the fixed-width format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The length of the bytes does not match `N` and the size of the integer type `I`.
+    WrongLength {
+        /// The expected number of bytes.
+        expected: usize,
+        /// The actual number of bytes.
+        actual: usize,
+    },
+    /// A coefficient is out of range, i.e. not reduced modulo `Q`, or not in `[-B, B]`
+    /// for the secret of a decryption key.
+    InvalidCoefficient {
+        /// The index of the coefficient, counting through all the polynomials.
+        index: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, actual } => {
+                write!(f, "wrong length: expected {expected} bytes, got {actual}")
+            }
+            DecodeError::InvalidCoefficient { index } => {
+                write!(f, "coefficient at index {index} is out of range")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Errors returned by [EncryptKey::try_encrypt](crate::EncryptKey::try_encrypt) when the
+/// message is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncryptError {
+    /// The length of the message is larger than `N`.
+    TooLong {
+        /// The length of the message.
+        len: usize,
+        /// The length of the polynomials.
+        n: usize,
+    },
+    /// The element at `index` of the message is neither 0 nor 1.
+    NonBinary {
+        /// The index of the first non-binary element.
+        index: usize,
+        /// The value of the element, or `i128::MAX` if it cannot be converted with
+        /// [IntField::to_word](crate::IntField::to_word).
+        value: i128,
+    },
+}
+
+impl fmt::Display for EncryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncryptError::TooLong { len, n } => {
+                write!(f, "message length {len} is larger than N={n}")
+            }
+            EncryptError::NonBinary { index, value } => {
+                write!(f, "message element {value} at index {index} is not binary")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncryptError {}
+
+/// Errors returned by [EncryptKey::encrypt_packed](crate::EncryptKey::encrypt_packed) when
+/// the messages cannot be packed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    /// The total length of the messages is larger than `N`.
+    TooLong {
+        /// The total length of the messages.
+        total: usize,
+        /// The length of the polynomials.
+        n: usize,
+    },
+    /// An element of a message is neither 0 nor 1.
+    NonBinary {
+        /// The index of the message.
+        message: usize,
+        /// The index of the first non-binary element in the message.
+        index: usize,
+        /// The value of the element, or `i128::MAX` if it cannot be converted with
+        /// [IntField::to_word](crate::IntField::to_word).
+        value: i128,
+    },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::TooLong { total, n } => {
+                write!(f, "total message length {total} is larger than N={n}")
+            }
+            PackError::NonBinary {
+                message,
+                index,
+                value,
+            } => write!(
+                f,
+                "element {value} at index {index} of message {message} is not binary"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PackError {}
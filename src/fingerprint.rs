@@ -0,0 +1,44 @@
+//! Defines the fingerprint of an encryption key, a stable identifier for logging and key
+//! management.
+
+use core::fmt;
+use num::ToPrimitive;
+use sha2::{Digest, Sha256};
+
+use crate::{EncryptKey, IntField};
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Returns the SHA-256 hash of the compact serialization of the key (see
+    /// [EncryptKey::to_compact]), i.e. the bit-packed coefficients of `a` followed by `t`.
+    /// Equal keys have the same fingerprint, while changing any coefficient changes it.
+    ///
+    /// ```
+    /// let rng = &mut rand::rng();
+    /// let (ek, _) = rlwe_encryption::standard(rng);
+    ///
+    /// assert_eq!(ek.fingerprint(), ek.clone().fingerprint());
+    /// // e.g. "EncryptKey(3f9a0c41d27be815)"
+    /// assert!(ek.to_string().starts_with("EncryptKey("));
+    /// ```
+    pub fn fingerprint(&self) -> [u8; 32]
+    where
+        Zq::I: ToPrimitive,
+    {
+        Sha256::digest(self.to_compact()).into()
+    }
+}
+
+/// Displays the key as `EncryptKey(<hex>)` with the first 8 bytes of its
+/// [fingerprint](EncryptKey::fingerprint).
+impl<Zq: IntField, const N: usize> fmt::Display for EncryptKey<Zq, N>
+where
+    Zq::I: ToPrimitive,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptKey(")?;
+        for b in &self.fingerprint()[..8] {
+            write!(f, "{b:02x}")?;
+        }
+        write!(f, ")")
+    }
+}
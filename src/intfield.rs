@@ -3,6 +3,24 @@
 use num::{Integer, One, Signed};
 use rand::distr::uniform::SampleUniform;
 
+use crate::polynomial::closest_integer_div_two;
+
+/// Requires the integer type `I` to implement [zeroize::Zeroize] when the `zeroize`
+/// feature is enabled, so that secret keys can be wiped. Without the feature, it is
+/// implemented for every type.
+#[cfg(feature = "zeroize")]
+pub trait MaybeZeroize: zeroize::Zeroize {}
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize> MaybeZeroize for T {}
+
+/// Requires the integer type `I` to implement [zeroize::Zeroize] when the `zeroize`
+/// feature is enabled, so that secret keys can be wiped. Without the feature, it is
+/// implemented for every type.
+#[cfg(not(feature = "zeroize"))]
+pub trait MaybeZeroize {}
+#[cfg(not(feature = "zeroize"))]
+impl<T> MaybeZeroize for T {}
+
 /// Implements a finite field over integers with prime modulus q.
 ///
 /// The value of `Q` and `B` must be carefully chosen in order to make it work.
@@ -17,13 +35,14 @@ use rand::distr::uniform::SampleUniform;
 /// library.
 pub trait IntField {
     #[cfg(not(any(feature = "serde")))]
-    type I: Integer + Signed + Clone + SampleUniform;
+    type I: Integer + Signed + Clone + SampleUniform + MaybeZeroize;
 
     #[cfg(feature = "serde")]
     type I: Integer
         + Signed
         + Clone
         + SampleUniform
+        + MaybeZeroize
         + serde::Serialize
         + for<'de> serde::Deserialize<'de>;
 
@@ -49,6 +68,62 @@ pub trait IntField {
     /// ```
     fn modulo(x: &Self::I) -> Self::I;
 
+    /// Applies [IntField::modulo] to each of the integers. It is used instead of
+    /// [IntField::modulo] on the coefficients of the polynomials with the `simd` feature if
+    /// [IntField::MODULO_SLICE] is true, so that a field can override it with a vectorized
+    /// implementation producing the same result, e.g.
+    /// [modulo_i32_slice](crate::modulo_i32_slice) for `I = i32`.
+    fn modulo_slice(xs: &mut [Self::I]) {
+        xs.iter_mut().for_each(|x| *x = Self::modulo(x));
+    }
+
+    /// Whether the field overrides [IntField::modulo_slice]. Defaults to false, in which
+    /// case the coefficients are reduced in place with [IntField::modulo], without copying
+    /// them into a slice.
+    const MODULO_SLICE: bool = false;
+
+    /// The reduction algorithm applied to the coefficients of the polynomials after each
+    /// arithmetic operation. Defaults to [Reduction::Modulo], i.e. [IntField::modulo].
+    /// The other algorithms require [IntField::to_word] and [IntField::from_word].
+    const REDUCTION: Reduction = Reduction::Modulo;
+
+    /// Whether the polynomials are multiplied with the negacyclic number theoretic transform
+    /// (NTT) in `O(N log N)`, instead of the schoolbook multiplication in `O(N^2)`. Defaults to
+    /// false. It requires `Q` to be a prime with `Q = 1 mod 2N`, and [IntField::to_word] and
+    /// [IntField::from_word], otherwise the schoolbook multiplication is used anyway.
+    const NTT: bool = false;
+
+    /// Converts `x` to an `i64`, for the algorithms working on machine words: the reductions
+    /// other than [Reduction::Modulo], the NTT and the rounding of the `constant-time`
+    /// feature. Returns `None` if `x` does not fit, or by default, in which case they fall
+    /// back to [IntField::modulo], the schoolbook multiplication and the branching rounding
+    /// respectively.
+    ///
+    /// A field over a primitive integer type can implement it with [ToPrimitive](num::ToPrimitive):
+    /// ```rust ignore
+    /// fn to_word(x: &Self::I) -> Option<i64> {
+    ///     x.to_i64()
+    /// }
+    /// ```
+    fn to_word(_x: &Self::I) -> Option<i64> {
+        None
+    }
+
+    /// Converts the `i64` back to the integer type `I`, the inverse of [IntField::to_word].
+    /// Returns `None` by default.
+    fn from_word(_x: i64) -> Option<Self::I> {
+        None
+    }
+
+    /// Returns the threshold `[q/2] / 2`, i.e. about `q/4`, used to round the coefficients in
+    /// the decryption: a coefficient decrypts to 1 if its absolute value is larger than the
+    /// threshold, and to 0 otherwise. Hence the decryption is correct as long as the noise
+    /// stays below it (see also [decryption_margin](crate::decryption_margin)).
+    fn decrypt_threshold() -> Self::I {
+        let two = Self::I::one() + Self::I::one();
+        closest_integer_div_two(Self::Q) / two
+    }
+
     /// Checks whether the parameters Q and B are valid for encryption:
     /// 2N * B^2 + B < Q/4
     fn valid() -> bool {
@@ -59,3 +134,148 @@ pub trait IntField {
         lhs < rhs
     }
 }
+
+/// The reduction algorithm selected by [IntField::REDUCTION]. All of them produce the same
+/// result as [IntField::modulo] (in the symmetric representation `[-Q/2, Q/2]`), but with
+/// different performance characteristics depending on the type `I` and the modulus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// Uses [IntField::modulo] as implemented by the field.
+    #[default]
+    Modulo,
+    /// Barrett reduction, replacing the division by a multiplication with the precomputed
+    /// `floor(2^64 / Q)` and a shift.
+    Barrett,
+    /// Montgomery reduction with `R = 2^64`, replacing the division by multiplications and
+    /// shifts. Requires an odd modulus, otherwise [IntField::modulo] is used.
+    Montgomery,
+}
+
+/// Reduces `x` into the field `Zq` with the algorithm selected by [IntField::REDUCTION].
+pub fn reduce<Zq: IntField>(x: &Zq::I) -> Zq::I {
+    Reducer::<Zq>::new().reduce(x)
+}
+
+/// Reduces integers into the field `Zq` with the constants of the algorithm selected by
+/// [IntField::REDUCTION] precomputed, so that they are shared among the coefficients of a
+/// polynomial.
+pub(crate) struct Reducer<Zq: IntField> {
+    q: u64,
+    constants: Constants,
+    _marker: core::marker::PhantomData<Zq>,
+}
+
+/// The precomputed constants of a [Reducer].
+enum Constants {
+    Modulo,
+    /// `floor(2^64 / q)`.
+    Barrett {
+        m: u128,
+    },
+    /// `-q^(-1) mod 2^64` and `2^128 mod q`.
+    Montgomery {
+        q_inv_neg: u64,
+        r2: u128,
+    },
+}
+
+impl<Zq: IntField> Reducer<Zq> {
+    pub(crate) fn new() -> Self {
+        // the reduced values are converted back with from_word, so q must fit in i64
+        let q = Zq::to_word(&Zq::Q)
+            .and_then(|q| u64::try_from(q).ok())
+            .unwrap_or(0);
+        let constants = match Zq::REDUCTION {
+            _ if q < 2 => Constants::Modulo,
+            Reduction::Modulo => Constants::Modulo,
+            Reduction::Barrett => Constants::Barrett {
+                m: (1u128 << 64) / q as u128,
+            },
+            Reduction::Montgomery if q % 2 == 1 => {
+                // Newton's iteration doubles the number of correct low bits of q^(-1),
+                // starting from 3 bits (q * q = 1 mod 8 for odd q).
+                let mut q_inv = q;
+                for _ in 0..5 {
+                    q_inv = q_inv.wrapping_mul(2u64.wrapping_sub(q.wrapping_mul(q_inv)));
+                }
+                let r = (1u128 << 64) % q as u128;
+                Constants::Montgomery {
+                    q_inv_neg: q_inv.wrapping_neg(),
+                    r2: r * r % q as u128,
+                }
+            }
+            Reduction::Montgomery => Constants::Modulo,
+        };
+        Self {
+            q,
+            constants,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Reduces `x` into the symmetric representation `[-Q/2, Q/2]`. Falls back to
+    /// [IntField::modulo] if `x` cannot be converted with [IntField::to_word].
+    #[inline]
+    pub(crate) fn reduce(&self, x: &Zq::I) -> Zq::I {
+        let a = match (&self.constants, Zq::to_word(x).map(i64::unsigned_abs)) {
+            (Constants::Modulo, _) | (_, None) => return Zq::modulo(x),
+            (Constants::Barrett { m }, Some(a)) => self.barrett(*m, a),
+            (Constants::Montgomery { q_inv_neg, r2 }, Some(a)) => {
+                let y = self.redc(*q_inv_neg, a as u128);
+                self.redc(*q_inv_neg, y as u128 * r2)
+            }
+        };
+        // a = |x| mod q, restore the sign and map to [-Q/2, Q/2]
+        let r = if x.is_negative() && a != 0 {
+            self.q - a
+        } else {
+            a
+        };
+        let Some(r) = Zq::from_word(r as i64) else {
+            return Zq::modulo(x);
+        };
+        if r > Zq::Q / (Zq::I::one() + Zq::I::one()) {
+            r - Zq::Q
+        } else {
+            r
+        }
+    }
+
+    /// Computes `a mod q` by Barrett reduction.
+    #[inline]
+    fn barrett(&self, m: u128, a: u64) -> u64 {
+        let t = ((a as u128 * m) >> 64) as u64;
+        let mut r = a - t * self.q; // in [0, 2q)
+        if r >= self.q {
+            r -= self.q;
+        }
+        r
+    }
+
+    /// Computes `t * 2^(-64) mod q` by Montgomery reduction, for `t < q * 2^64`.
+    #[inline]
+    fn redc(&self, q_inv_neg: u64, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(q_inv_neg);
+        // t + m * q is divisible by 2^64, and the sum may overflow u128 by one bit
+        let (sum, carry) = t.overflowing_add(m as u128 * self.q as u128);
+        let u = (sum >> 64) | ((carry as u128) << 64);
+        let u = u as u64;
+        if u >= self.q {
+            u - self.q
+        } else {
+            u
+        }
+    }
+}
+
+/// Converts the field element `x` in the symmetric representation `[-Q/2, Q/2]` (as returned
+/// by [IntField::modulo]) to the standard representation `[0, Q)`.
+pub fn to_standard_rep<Zq: IntField>(x: &Zq::I) -> Zq::I {
+    x.mod_floor(&Zq::Q)
+}
+
+/// Converts the field element `x` in the standard representation `[0, Q)` to the symmetric
+/// representation `[-Q/2, Q/2]`.
+pub fn to_symmetric_rep<Zq: IntField>(x: &Zq::I) -> Zq::I {
+    Zq::modulo(x)
+}
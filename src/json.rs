@@ -0,0 +1,25 @@
+//! Convenience methods to convert keys and ciphertexts to and from JSON.
+
+use crate::{CipherText, DecryptKey, EncryptKey, Error, IntField};
+use alloc::string::String;
+
+macro_rules! impl_json {
+    ($name:ident) => {
+        impl<Zq: IntField, const N: usize> $name<Zq, N> {
+            /// Serializes into a JSON string.
+            pub fn to_json(&self) -> String {
+                // serialization cannot fail as the struct contains only integers
+                serde_json::to_string(self).unwrap()
+            }
+
+            /// Deserializes from a JSON string produced by `to_json`.
+            pub fn from_json(json: &str) -> Result<Self, Error> {
+                serde_json::from_str(json).map_err(|_| Error::InvalidJson)
+            }
+        }
+    };
+}
+
+impl_json!(EncryptKey);
+impl_json!(DecryptKey);
+impl_json!(CipherText);
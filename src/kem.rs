@@ -0,0 +1,129 @@
+//! Defines a key encapsulation mechanism (KEM) on top of the encryption scheme.
+//!
+//! ## Security
+//!
+//! The KEM is **not CCA-secure**, i.e. it must not be used where an attacker can submit
+//! ciphertexts for decapsulation and observe the result. There is no Fujisaki-Okamoto
+//! transform: the decapsulation does not re-encrypt the message to check the ciphertext,
+//! and has no implicit rejection. A malformed ciphertext is decapsulated like a valid one,
+//! into a secret influenced by the attacker, which can leak the decryption key over many
+//! queries. It is only suitable against passive (CPA) attackers, or with one-time keys.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::{CipherText, DecryptKey, EncryptKey, IntField, Message};
+
+/// The domain separation tag for hashing the encapsulated message into the shared secret.
+const KEM_DOMAIN: &[u8] = b"rlwe-encryption/kem";
+/// The domain separation tag for hashing the encapsulated message into a channel key.
+const CHANNEL_DOMAIN: &[u8] = b"rlwe-encryption/channel";
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Encapsulates a shared secret. A random message of `N` bits is encrypted, and the
+    /// message is hashed with SHA-256 into the 32-byte shared secret. Returns the ciphertext
+    /// to be sent to the owner of the decryption key, and the shared secret.
+    ///
+    /// The KEM is not CCA-secure: there is no Fujisaki-Okamoto re-encryption check or
+    /// implicit rejection in [DecryptKey::decapsulate], so it must not be used where an
+    /// attacker can have arbitrary ciphertexts decapsulated.
+    ///
+    /// ```
+    /// use rlwe_encryption::{key_gen, StandardZq};
+    ///
+    /// let rng = &mut rand::rng();
+    /// let (ek, dk) = key_gen::<StandardZq, 256>(rng);
+    /// let (c, secret) = ek.encapsulate(rng);
+    /// assert_eq!(dk.decapsulate(&c), secret);
+    /// ```
+    pub fn encapsulate(&self, rng: &mut impl Rng) -> (CipherText<Zq, N>, [u8; 32])
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let m = Message::<Zq, N>::random(rng, N);
+        let secret = shared_secret(&m);
+        (self.encrypt(rng, m), secret)
+    }
+
+    /// Encapsulates a channel key bound to the `context` (e.g. the identities of the
+    /// parties and the protocol name), like [EncryptKey::encapsulate]. The owner of the
+    /// decryption key derives the same key with [DecryptKey::derive_channel_key] and the
+    /// same context, while a different context gives an unrelated key.
+    ///
+    /// Like [EncryptKey::encapsulate], it is not CCA-secure.
+    pub fn encapsulate_channel_key(
+        &self,
+        rng: &mut impl Rng,
+        context: &[u8],
+    ) -> (CipherText<Zq, N>, [u8; 32])
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let m = Message::<Zq, N>::random(rng, N);
+        let key = channel_key(&m, context);
+        (self.encrypt(rng, m), key)
+    }
+}
+
+impl<Zq: IntField, const N: usize> DecryptKey<Zq, N> {
+    /// Decapsulates the shared secret from a ciphertext produced by [EncryptKey::encapsulate],
+    /// by decrypting the message and hashing it again.
+    ///
+    /// It is not CCA-secure: the ciphertext is not checked by re-encryption and there is no
+    /// implicit rejection, so a malformed ciphertext yields a secret influenced by the
+    /// attacker instead of an error or a pseudorandom secret. Over many queries, this can
+    /// leak the decryption key.
+    pub fn decapsulate(&self, c: &CipherText<Zq, N>) -> [u8; 32]
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let data = self.decrypt(c.clone());
+        shared_secret(&Message::<Zq, N> { data })
+    }
+
+    /// Derives the channel key bound to the `context` from a ciphertext produced by
+    /// [EncryptKey::encapsulate_channel_key], by decrypting the message and hashing it with
+    /// the context.
+    ///
+    /// Like [DecryptKey::decapsulate], it is not CCA-secure: a malformed ciphertext yields a
+    /// key influenced by the attacker.
+    ///
+    /// ```
+    /// use rlwe_encryption::{key_gen, StandardZq};
+    ///
+    /// let rng = &mut rand::rng();
+    /// let (ek, dk) = key_gen::<StandardZq, 256>(rng);
+    /// let (c, key) = ek.encapsulate_channel_key(rng, b"alice to bob");
+    /// assert_eq!(dk.derive_channel_key(&c, b"alice to bob"), key);
+    /// ```
+    pub fn derive_channel_key(&self, c: &CipherText<Zq, N>, context: &[u8]) -> [u8; 32]
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let data = self.decrypt(c.clone());
+        channel_key(&Message::<Zq, N> { data }, context)
+    }
+}
+
+/// Hashes the bits of the message `m` into the shared secret.
+fn shared_secret<Zq: IntField, const N: usize>(m: &Message<Zq, N>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(KEM_DOMAIN);
+    hasher.update(m.to_bytes());
+    hasher.finalize().into()
+}
+
+/// Hashes the context and the bits of the message `m` into the channel key. The context is
+/// prefixed with its length, so that it cannot be confused with the message.
+fn channel_key<Zq: IntField, const N: usize>(m: &Message<Zq, N>, context: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(CHANNEL_DOMAIN);
+    hasher.update((context.len() as u64).to_le_bytes());
+    hasher.update(context);
+    hasher.update(m.to_bytes());
+    hasher.finalize().into()
+}
@@ -0,0 +1,51 @@
+//! Defines the KeyPair struct holding both the encryption and decryption keys.
+
+use core::ops::{Add, Mul, Neg, Sub};
+use rand::Rng;
+
+use crate::{DecryptKey, EncryptKey, IntField, Message};
+
+/// A pair of encryption and decryption keys.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair<Zq: IntField, const N: usize> {
+    /// The encryption key.
+    pub ek: EncryptKey<Zq, N>,
+    /// The decryption key.
+    pub dk: DecryptKey<Zq, N>,
+}
+
+impl<Zq: IntField, const N: usize> KeyPair<Zq, N> {
+    /// Creates a key pair from the encryption and decryption keys.
+    pub fn new(ek: EncryptKey<Zq, N>, dk: DecryptKey<Zq, N>) -> Self {
+        Self { ek, dk }
+    }
+
+    /// Encrypts the message `m` with the encryption key, decrypts it with the decryption
+    /// key, and returns whether the message round-trips. It is a quick sanity check, e.g.
+    /// after loading the keys from storage.
+    pub fn self_test(&self, rng: &mut impl Rng, m: Message<Zq, N>) -> bool
+    where
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let len = m.len();
+        let c = self.ek.encrypt(
+            rng,
+            Message {
+                data: m.data.clone(),
+            },
+        );
+        self.dk.decrypt_exact(c, len) == m.data
+    }
+}
+
+impl<Zq: IntField, const N: usize> From<(EncryptKey<Zq, N>, DecryptKey<Zq, N>)> for KeyPair<Zq, N> {
+    fn from((ek, dk): (EncryptKey<Zq, N>, DecryptKey<Zq, N>)) -> Self {
+        Self { ek, dk }
+    }
+}
@@ -1,20 +1,105 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "ndarray")]
+mod arrays;
+#[cfg(feature = "ndarray")]
+pub use arrays::{from_ndarray, to_ndarray};
+#[cfg(feature = "bigint")]
+mod bigint;
+#[cfg(feature = "bigint")]
+pub use bigint::{BigI, UniformBigI, ZqBig};
+mod chunked;
+pub use chunked::{IndexedCipherText, StreamDecryptor, StreamEncryptor};
 mod ciphertext;
-pub use ciphertext::CipherText;
+pub use ciphertext::{
+    ciphertext_correlation, ciphertext_size, try_weighted_sum, weighted_sum, CipherText,
+};
+mod codec;
+#[cfg(feature = "serde")]
+mod compact;
+#[cfg(feature = "json")]
+pub use codec::JsonCodec;
+pub use codec::{CompactCodec, KeyCodec};
+#[cfg(feature = "serde")]
+pub use compact::CompactCipherText;
+mod crs;
+pub use crs::{protocol_digest, Crs};
 mod decrypt;
-pub use decrypt::DecryptKey;
+mod delta;
+pub use decrypt::{decrypt_key_size, decryption_margin, DecryptKey};
+pub use delta::CipherTextDelta;
+mod dynfield;
+pub use dynfield::{DynField, DynFieldCipherText, DynFieldDecryptKey, DynFieldEncryptKey};
 mod encrypt;
-pub use encrypt::EncryptKey;
+pub use encrypt::{encrypt_key_size, encrypt_redundant, EncryptKey, Opening, MAX_NOISE_RETRIES};
+mod envelope;
+pub use envelope::CipherTextEnvelope;
+mod error;
+pub use error::{DecodeError, EncryptError, Error, KeyError, PackError, ParamError};
+#[cfg(feature = "fingerprint")]
+mod fingerprint;
 mod intfield;
-pub use intfield::IntField;
+pub use intfield::{reduce, to_standard_rep, to_symmetric_rep, IntField, Reduction};
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "kem")]
+mod kem;
+mod keypair;
+pub use keypair::KeyPair;
+mod log;
+pub use log::{log_append, log_verify_chain, LogEntry, LOG_GENESIS_ID};
 mod message;
-pub use message::Message;
+pub use message::{BitOrder, Message};
+#[cfg(feature = "noise-tracking")]
+mod noise;
+#[cfg(feature = "noise-tracking")]
+pub use noise::NoiseTracked;
+mod ntt;
+#[cfg(feature = "test-utils")]
+pub use ntt::verify_ntt_correctness;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod params;
+pub use params::{
+    check_parameters, check_params, diagnose_params, estimate_failure_probability,
+    estimate_security_bits, max_homomorphic_additions, min_modulus_for, min_prime_modulus_for,
+    recommend_params, stress_test_field, ParamsManifest, StressReport, ValidParams,
+};
 pub(crate) mod polynomial;
+mod proof;
+pub use proof::{
+    prove_bit, prove_decryption, verify_bit, verify_decryption, BitProof, DecryptionProof,
+    MAX_PROOF_RETRIES,
+};
+mod rekey;
+pub use poly_ring_xnp1::Polynomial;
+pub use rekey::{reencrypt_batch, ReKey};
+mod session;
+pub use session::Session;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "simd")]
+pub use simd::modulo_i32_slice;
+mod threshold;
+pub use threshold::{
+    combine_partials_verified, joint_encrypt_key, partial_decrypt, PartialDecryption,
+};
+#[cfg(feature = "json")]
+mod vectors;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "json")]
+pub use vectors::{export_test_vectors, TestVector, TestVectors};
+pub(crate) mod xof;
 
-use polynomial::{modulo_coefficients, rand_polynomial, small_polynomial};
-use rand::Rng;
-use std::ops::{Add, Mul, Sub};
+use alloc::string::ToString;
+use core::ops::{Add, Mul, Sub};
+use num::ToPrimitive;
+use polynomial::{modulo_coefficients, mul_polynomials, rand_polynomial, small_polynomial};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 /// Generate a pair of encryption and decryption keys from the parameters
 /// defined in the generic type `Zq` and the value of `N`.
@@ -27,6 +112,9 @@ use std::ops::{Add, Mul, Sub};
 /// The constant `N` defines the length of the polynomial, as well as the maximum
 /// length of the message that can be encrypted. It must be a power of 2.
 ///
+/// The parameters are not checked. Use [try_key_gen] to reject invalid parameters with
+/// an error instead.
+///
 /// ## Example
 ///
 /// ```rust
@@ -63,22 +151,271 @@ use std::ops::{Add, Mul, Sub};
 pub fn key_gen<Zq: IntField, const N: usize>(
     rng: &mut impl Rng,
 ) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    key_gen_unchecked(rng)
+}
+
+/// Generate a pair of encryption and decryption keys like [key_gen], but returns the
+/// [ParamError] of [check_parameters] instead of the keys if the parameters are invalid,
+/// e.g. `N` is not a power of 2 ([ParamError::NotPowerOfTwo]), or the condition
+/// `2N * B^2 + B < Q/4` does not hold ([ParamError::ParametersTooSmall]).
+///
+/// ## Example
+///
+/// ```rust
+/// use rlwe_encryption::{try_key_gen, ParamError, StandardZq};
+///
+/// let rng = &mut rand::rng();
+///
+/// assert!(try_key_gen::<StandardZq, 256>(rng).is_ok());
+/// assert_eq!(
+///     try_key_gen::<StandardZq, 300>(rng).err(),
+///     Some(ParamError::NotPowerOfTwo { n: 300 })
+/// );
+/// assert_eq!(
+///     try_key_gen::<StandardZq, 512>(rng).err(),
+///     Some(ParamError::ParametersTooSmall { lhs: 1025, rhs: 832 })
+/// );
+/// ```
+pub fn try_key_gen<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+) -> Result<(EncryptKey<Zq, N>, DecryptKey<Zq, N>), ParamError>
+where
+    Zq::I: ToPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    check_parameters::<Zq, N>()?;
+    Ok(key_gen_unchecked(rng))
+}
+
+/// Generate a pair of encryption and decryption keys without checking the parameters.
+pub(crate) fn key_gen_unchecked<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
 where
     for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
 {
     let a = rand_polynomial::<Zq, N>(rng);
+    key_gen_with_a(a, rng)
+}
+
+/// Generate a pair of encryption and decryption keys like [key_gen], but the parameters
+/// are checked at compile time by the bound [ValidParams], which is implemented only for
+/// the pre-defined parameter sets satisfying `2N * B^2 + B < Q/4`.
+///
+/// ## Example
+///
+/// ```rust
+/// use rlwe_encryption::{key_gen_checked, Message, StandardZq};
+///
+/// let rng = &mut rand::rng();
+///
+/// let (ek, dk) = key_gen_checked::<StandardZq, 256>(rng);
+///
+/// let message = Message::<StandardZq, 256>::new(vec![0, 1, 0, 1]);
+/// let ciphertext = ek.encrypt(rng, message.clone());
+/// assert_eq!(message.data(), dk.decrypt_exact(ciphertext, 4));
+/// ```
+pub fn key_gen_checked<Zq: ValidParams<N>, const N: usize>(
+    rng: &mut impl Rng,
+) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    key_gen_unchecked::<Zq, N>(rng)
+}
+
+/// Generate a pair of encryption and decryption keys like [key_gen], but the parameters
+/// are checked at runtime first by [diagnose_params]. Returns [Error::MisconfiguredParams]
+/// with an actionable message if they are wrong, instead of the keys which would fail to
+/// decrypt.
+///
+/// ## Example
+///
+/// ```rust
+/// use rlwe_encryption::{key_gen_diagnosed, IntField};
+///
+/// struct TinyZq;
+///
+/// impl IntField for TinyZq {
+///     type I = i32;
+///     const Q: i32 = 97;
+///     const B: i32 = 1;
+///
+///     fn modulo(x: &i32) -> i32 {
+///         let a = x.rem_euclid(Self::Q);
+///         if a > Self::Q / 2 {
+///             a - Self::Q
+///         } else {
+///             a
+///         }
+///     }
+/// }
+///
+/// let err = key_gen_diagnosed::<TinyZq, 256>(&mut rand::rng()).err().unwrap();
+/// assert!(err.to_string().contains("need Q > 2052, try Q=2053"));
+/// ```
+pub fn key_gen_diagnosed<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+) -> Result<(EncryptKey<Zq, N>, DecryptKey<Zq, N>), Error>
+where
+    Zq::I: ToPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let (q, b) = (Zq::Q.to_u64(), Zq::B.to_u64());
+    match (q, b) {
+        (Some(q), Some(b)) => diagnose_params(N, q, b)?,
+        _ => {
+            return Err(Error::MisconfiguredParams(
+                "Q and B must be positive".to_string(),
+            ))
+        }
+    }
+    Ok(key_gen::<Zq, N>(rng))
+}
+
+/// Generate a pair of encryption and decryption keys which shares the same public
+/// polynomial `a` (the common reference string) as the given encryption key.
+///
+/// This is useful for multi-party protocols where all parties use the same `a`.
+/// Use [EncryptKey::same_crs] to check whether two keys share the same `a`.
+///
+/// ## Example
+///
+/// ```rust
+/// let rng = &mut rand::rng();
+///
+/// let (ek1, _) = rlwe_encryption::standard(rng);
+/// let (ek2, _) = rlwe_encryption::key_gen_from_crs(&ek1, rng);
+/// assert!(ek1.same_crs(&ek2));
+/// ```
+pub fn key_gen_from_crs<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    rng: &mut impl Rng,
+) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    key_gen_with_a(ek.a.clone(), rng)
+}
+
+/// Generate a pair of encryption and decryption keys where the public polynomial `a`
+/// is derived deterministically from a public `label` (e.g. a protocol identifier),
+/// while the secrets are sampled from `rng`.
+///
+/// The polynomial `a` is sampled from the output of SHAKE128 over the label, so all
+/// parties of a named protocol share the same `a` without further coordination.
+///
+/// ## Example
+///
+/// ```rust
+/// use rlwe_encryption::{key_gen_with_label, StandardZq};
+///
+/// let rng = &mut rand::rng();
+///
+/// let (ek1, dk1) = key_gen_with_label::<StandardZq, 256>(b"my protocol", rng);
+/// let (ek2, dk2) = key_gen_with_label::<StandardZq, 256>(b"my protocol", rng);
+/// assert!(ek1.same_crs(&ek2));
+/// assert_ne!(dk1, dk2);
+/// ```
+pub fn key_gen_with_label<Zq: IntField, const N: usize>(
+    label: &[u8],
+    rng: &mut impl Rng,
+) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    key_gen_with_a(Crs::<Zq, N>::from_label(label).a, rng)
+}
+
+/// Generate a pair of encryption and decryption keys from the given public polynomial `a`.
+fn key_gen_with_a<Zq: IntField, const N: usize>(
+    a: Polynomial<Zq::I, N>,
+    rng: &mut impl Rng,
+) -> (EncryptKey<Zq, N>, DecryptKey<Zq, N>)
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
     let s = small_polynomial::<Zq, N>(rng);
     let e = small_polynomial::<Zq, N>(rng);
 
     // t = a * s + e
     let t = {
-        let a_s = modulo_coefficients::<Zq, N>(a.clone() * s.clone());
+        let a_s = mul_polynomials::<Zq, N>(&a, &s);
         modulo_coefficients::<Zq, N>(a_s + e)
     };
 
     (EncryptKey { a, t }, DecryptKey { s })
 }
 
+/// The minimum number of distinct byte values a seed must contain to be accepted by
+/// [key_gen_from_seed]. A uniformly random 32-byte seed has far more distinct values
+/// with overwhelming probability.
+const MIN_DISTINCT_SEED_BYTES: usize = 16;
+
+/// Deterministically generate a pair of encryption and decryption keys from a 32-byte seed.
+///
+/// The same seed always produces the same keys, so the seed must be kept as secret as the
+/// decryption key. As a guardrail against misuse, seeds with obviously low entropy (e.g. all
+/// bytes being identical, or containing only a few distinct byte values) are rejected with
+/// [Error::LowEntropySeed].
+///
+/// ## Example
+///
+/// ```rust
+/// use rand::RngCore;
+/// use rlwe_encryption::{key_gen_from_seed, StandardZq};
+///
+/// let mut seed = [0u8; 32];
+/// rand::rng().fill_bytes(&mut seed);
+///
+/// let (ek1, dk1) = key_gen_from_seed::<StandardZq, 256>(seed).unwrap();
+/// let (ek2, dk2) = key_gen_from_seed::<StandardZq, 256>(seed).unwrap();
+/// assert_eq!(ek1, ek2);
+/// assert_eq!(dk1, dk2);
+///
+/// assert!(key_gen_from_seed::<StandardZq, 256>([0u8; 32]).is_err());
+/// ```
+pub fn key_gen_from_seed<Zq: IntField, const N: usize>(
+    seed: [u8; 32],
+) -> Result<(EncryptKey<Zq, N>, DecryptKey<Zq, N>), Error>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let mut seen = [false; 256];
+    seed.iter().for_each(|&b| seen[b as usize] = true);
+    if seen.iter().filter(|&&b| b).count() < MIN_DISTINCT_SEED_BYTES {
+        return Err(Error::LowEntropySeed);
+    }
+
+    let rng = &mut StdRng::from_seed(seed);
+    Ok(key_gen_unchecked::<Zq, N>(rng))
+}
+
+/// The reduction polynomial `f(X)` of the ring `Zq[X]/(f(X))` over which the scheme operates.
+///
+/// The ring arithmetic is provided by the crate [poly_ring_xnp1], so the scheme is
+/// currently fixed to the negacyclic ring `X^N + 1` (see [RING_MODULUS]). All polynomial
+/// operations of this library go through its `Polynomial` type, hence supporting another
+/// ring amounts to swapping that type for one implementing the same arithmetic. Note that
+/// the cyclic ring `X^N - 1` is not recommended for encryption, as `X - 1` divides `X^N - 1`
+/// and evaluating at `X = 1` exposes a much smaller LWE instance.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RingModulus {
+    /// `f(X) = X^N + 1`, i.e. polynomial multiplication is a negacyclic convolution.
+    Negacyclic,
+}
+
+/// The reduction polynomial used by this library.
+///
+/// ```
+/// assert_eq!(rlwe_encryption::RING_MODULUS, rlwe_encryption::RingModulus::Negacyclic);
+/// ```
+pub const RING_MODULUS: RingModulus = RingModulus::Negacyclic;
+
 /// A pre-defined field over integers with prime modulus 3329.
 /// The parameters are chosen according to the NIST standard
 /// [FIPS203](https://csrc.nist.gov/pubs/fips/203/final).
@@ -95,14 +432,47 @@ impl IntField for StandardZq {
     const Q: i32 = 3329;
     const B: i32 = 1;
 
+    /// Uses Barrett reduction with a precomputed constant instead of the division
+    /// in `rem_euclid`.
+    #[inline]
     fn modulo(x: &Self::I) -> Self::I {
-        let a = x.rem_euclid(Self::Q);
-        if a > Self::Q / 2 {
-            a - Self::Q
-        } else {
-            a
-        }
+        barrett_reduce_3329(*x)
+    }
+
+    fn to_word(x: &Self::I) -> Option<i64> {
+        Some(i64::from(*x))
     }
+
+    fn from_word(x: i64) -> Option<Self::I> {
+        i32::try_from(x).ok()
+    }
+
+    /// Uses the vectorized reduction [modulo_i32_slice].
+    #[cfg(feature = "simd")]
+    fn modulo_slice(xs: &mut [Self::I]) {
+        modulo_i32_slice(xs, Self::Q)
+    }
+
+    #[cfg(feature = "simd")]
+    const MODULO_SLICE: bool = true;
+}
+
+/// Barrett constant `floor(2^32 / 3329)` used by [barrett_reduce_3329].
+const BARRETT_V_3329: i64 = (1 << 32) / 3329;
+
+/// Reduces `x` modulo 3329 into the range `[-(q-1)/2, (q-1)/2]` without division.
+///
+/// The quotient estimate `t = floor(x * V / 2^32)` is off by at most one from `floor(x / q)`,
+/// so the remainder `x - t * q` lies in `[-q, 2q)` and is corrected with branchless masks.
+#[inline]
+fn barrett_reduce_3329(x: i32) -> i32 {
+    const Q: i32 = StandardZq::Q;
+    let t = (x as i64 * BARRETT_V_3329) >> 32;
+    let mut r = (x as i64 - t * Q as i64) as i32; // in [-q, 2q)
+    r += (r >> 31) & Q; // in [0, 2q)
+    r -= ((Q - 1 - r) >> 31) & Q; // in [0, q)
+    r -= ((Q / 2 - r) >> 31) & Q; // in [-(q-1)/2, (q-1)/2]
+    r
 }
 
 /// Generate a pair of encryption and decryption keys using the parameters
@@ -124,5 +494,71 @@ impl IntField for StandardZq {
 pub fn standard(
     rng: &mut impl rand::Rng,
 ) -> (EncryptKey<StandardZq, 256>, DecryptKey<StandardZq, 256>) {
-    key_gen::<StandardZq, 256>(rng)
+    key_gen_checked::<StandardZq, 256>(rng)
+}
+
+/// A pre-defined field over `i64` integers with the prime modulus 16760833 and `B = 2`,
+/// for `N = 1024` (see [standard_i64]). The modulus is NTT-friendly, i.e. `Q = 1 mod 2N`.
+///
+/// Compared with [StandardZq] (`N = 256`), it encrypts 1024 bits per ciphertext with a
+/// wider noise distribution, at the cost of larger keys and ciphertexts:
+///
+/// | Preset | N | Q | B | Encryption key | Decryption key | Ciphertext |
+/// |---|---|---|---|---|---|---|
+/// | [StandardZq] | 256 | 3329 | 1 | 768 bytes | 384 bytes | 768 bytes |
+/// | [ZqI64] | 1024 | 16760833 | 2 | 6144 bytes | 3072 bytes | 6144 bytes |
+///
+/// The sizes are of the compact format (see [ciphertext_size]).
+///
+/// ```
+/// use rlwe_encryption::{ciphertext_size, IntField, ZqI64};
+///
+/// assert!(ZqI64::valid());
+/// assert_eq!(ciphertext_size::<ZqI64, 1024>(), 6144);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZqI64;
+
+impl IntField for ZqI64 {
+    type I = i64;
+    const Q: i64 = 16760833;
+    const B: i64 = 2;
+    const NTT: bool = true;
+
+    #[inline]
+    fn modulo(x: &Self::I) -> Self::I {
+        let a = x.rem_euclid(Self::Q);
+        if a > Self::Q / 2 {
+            a - Self::Q
+        } else {
+            a
+        }
+    }
+
+    fn to_word(x: &Self::I) -> Option<i64> {
+        Some(*x)
+    }
+
+    fn from_word(x: i64) -> Option<Self::I> {
+        Some(x)
+    }
+}
+
+/// Generate a pair of encryption and decryption keys with the pre-defined field [ZqI64]
+/// and `N = 1024`, i.e. the length of the message is at most 1024 bits.
+///
+/// ```rust
+/// let rng = &mut rand::rng();
+///
+/// let (ek, dk) = rlwe_encryption::standard_i64(rng);
+///
+/// let message = rlwe_encryption::Message::<_, 1024>::new(vec![0, 1, 0, 1]);
+/// let ciphertext = ek.encrypt(rng, message.clone());
+/// let decrypted = dk.decrypt(ciphertext)[..message.len()].to_vec();
+/// assert_eq!(message.data(), decrypted);
+/// ```
+pub fn standard_i64(
+    rng: &mut impl rand::Rng,
+) -> (EncryptKey<ZqI64, 1024>, DecryptKey<ZqI64, 1024>) {
+    key_gen_checked::<ZqI64, 1024>(rng)
 }
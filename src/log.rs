@@ -0,0 +1,87 @@
+//! Defines the LogEntry struct for an append-only log of chained ciphertexts.
+
+use alloc::vec::Vec;
+use core::fmt;
+use num::ToPrimitive;
+use rand::RngCore;
+
+use crate::{xof::XofRng, CipherText, IntField};
+
+/// The domain separation tag for chaining the entries in [LogEntry::id].
+const LOG_CHAIN_DOMAIN: &[u8] = b"rlwe-encryption/log-chain";
+
+/// The previous id of the first entry of a log.
+pub const LOG_GENESIS_ID: [u8; 16] = [0u8; 16];
+
+/// An entry of a tamper-evident append-only log, i.e. a ciphertext chained to the previous
+/// entry by its id. Reordering or deleting entries in the middle of the log breaks the
+/// chain (see [log_verify_chain]). Please note that truncating the end of the log cannot be
+/// detected from the log alone, so the id of the last entry should be kept elsewhere.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct LogEntry<Zq: IntField, const N: usize> {
+    /// The ciphertext.
+    pub ciphertext: CipherText<Zq, N>,
+    /// The id of the previous entry, or [LOG_GENESIS_ID] for the first entry.
+    pub prev_id: [u8; 16],
+}
+
+/// Formats the entry. Unlike the derived [Debug], which only requires `Zq: Debug`, it
+/// requires the ciphertext to implement [Debug].
+impl<Zq: IntField, const N: usize> fmt::Debug for LogEntry<Zq, N>
+where
+    CipherText<Zq, N>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogEntry")
+            .field("ciphertext", &self.ciphertext)
+            .field("prev_id", &self.prev_id)
+            .finish()
+    }
+}
+
+impl<Zq: IntField, const N: usize> LogEntry<Zq, N> {
+    /// Returns the id of the entry, i.e. the truncated SHAKE128 hash of the id of the
+    /// previous entry and the id of the ciphertext (see [CipherText::id]).
+    pub fn id(&self) -> [u8; 16]
+    where
+        Zq::I: ToPrimitive,
+    {
+        let input = [self.prev_id, self.ciphertext.id()].concat();
+        let mut id = [0u8; 16];
+        XofRng::new(LOG_CHAIN_DOMAIN, &input).fill_bytes(&mut id);
+        id
+    }
+}
+
+/// Appends the ciphertext to the log, chained to the last entry.
+pub fn log_append<Zq: IntField, const N: usize>(
+    log: &mut Vec<LogEntry<Zq, N>>,
+    ciphertext: CipherText<Zq, N>,
+) where
+    Zq::I: ToPrimitive,
+{
+    let prev_id = log.last().map_or(LOG_GENESIS_ID, LogEntry::id);
+    log.push(LogEntry {
+        ciphertext,
+        prev_id,
+    });
+}
+
+/// Verifies that each entry of the log is chained to the previous one, and the first
+/// entry starts from [LOG_GENESIS_ID].
+pub fn log_verify_chain<Zq: IntField, const N: usize>(log: &[LogEntry<Zq, N>]) -> bool
+where
+    Zq::I: ToPrimitive,
+{
+    let mut prev_id = LOG_GENESIS_ID;
+    log.iter().all(|entry| {
+        let chained = entry.prev_id == prev_id;
+        prev_id = entry.id();
+        chained
+    })
+}
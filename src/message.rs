@@ -1,8 +1,12 @@
 //! Defines the Message struct and its associated methods.
 
+use alloc::{vec, vec::Vec};
 use num::{One, Zero};
 
-use crate::IntField;
+use crate::{EncryptError, Error, IntField};
+
+/// The number of bytes used by the length header in [Message::to_bytes].
+const LENGTH_HEADER_SIZE: usize = 4;
 
 /// Represents a message to be encrypted or decrypted.
 ///
@@ -20,14 +24,25 @@ impl<Zq: IntField, const N: usize> Message<Zq, N> {
     /// ## Safety
     /// Message `m` must be a vector of integers in {0, 1}, i.e. binary message.
     /// and the length of the message must be less than or equal to `N`.
+    /// Use [Message::try_new] for untrusted input.
+    ///
+    /// The input is validated (and panics if invalid) only with the `checked` feature.
+    /// By default, the checks are skipped for performance.
     pub fn new(data: Vec<Zq::I>) -> Self {
-        assert!(data.len() <= N);
-        data.iter()
-            .for_each(|mi| assert!(mi == &Zq::I::zero() || mi == &Zq::I::one()));
+        #[cfg(feature = "checked")]
+        return Self::try_new(data).expect("message must be binary with length at most N");
 
+        #[cfg(not(feature = "checked"))]
         Self { data }
     }
 
+    /// Creates a new message from a vector of integers. Returns [Error::InvalidMessage]
+    /// if the message is not binary or its length is larger than `N`.
+    pub fn try_new(data: Vec<Zq::I>) -> Result<Self, Error> {
+        validate_message::<Zq, N>(&data).map_err(|_| Error::InvalidMessage)?;
+        Ok(Self { data })
+    }
+
     /// Returns the length of the message.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -43,8 +58,20 @@ impl<Zq: IntField, const N: usize> Message<Zq, N> {
         self.data
     }
 
+    /// Creates a random binary message of length `len`.
+    ///
+    /// ## Safety
+    /// `len` must be less than or equal to `N`. Use [Message::try_random] for untrusted input.
     pub fn random<R: rand::Rng>(rng: &mut R, len: usize) -> Self {
-        assert!(len <= N);
+        Self::try_random(rng, len).expect("length must be at most N")
+    }
+
+    /// Creates a random binary message of length `len`. Returns [Error::InvalidLength]
+    /// if `len` is larger than `N`.
+    pub fn try_random<R: rand::Rng>(rng: &mut R, len: usize) -> Result<Self, Error> {
+        if len > N {
+            return Err(Error::InvalidLength);
+        }
         let data = (0..len)
             .map(|_| {
                 if rng.random_bool(0.5) {
@@ -55,10 +82,185 @@ impl<Zq: IntField, const N: usize> Message<Zq, N> {
             })
             .collect();
 
+        Ok(Self { data })
+    }
+
+    /// Serializes the message into bytes. The output begins with a 4-byte
+    /// little-endian length header (the number of bits), followed by the bits
+    /// packed into bytes in LSB-first order.
+    ///
+    /// The header makes the encoding self-describing, so [Message::from_bytes]
+    /// recovers the exact bit count even if it is not a multiple of 8.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_order(BitOrder::LsbFirst)
+    }
+
+    /// Serializes the message into bytes like [Message::to_bytes], packing the bits
+    /// within each byte in the given order.
+    pub fn to_bytes_with_order(&self, order: BitOrder) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(LENGTH_HEADER_SIZE + self.data.len().div_ceil(8));
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        bytes.extend(self.to_raw_bytes(order));
+        bytes
+    }
+
+    /// Packs the bits into bytes in the given order, without the length header of
+    /// [Message::to_bytes]. If the length is not a multiple of 8, the last byte is padded
+    /// with zero bits, so the exact length must be known to the receiver.
+    pub fn to_raw_bytes(&self, order: BitOrder) -> Vec<u8> {
+        self.data
+            .chunks(8)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0u8, |acc, (i, mi)| {
+                    acc | ((mi.is_one() as u8) << order.shift(i))
+                })
+            })
+            .collect()
+    }
+
+    /// Creates a message from arbitrary data, expanding each byte into 8 bits in the given
+    /// order, i.e. the inverse of [Message::to_raw_bytes]. Returns [Error::InvalidMessage]
+    /// if there are more than `N` bits.
+    pub fn from_raw_bytes(bytes: &[u8], order: BitOrder) -> Result<Self, Error> {
+        if bytes.len() * 8 > N {
+            return Err(Error::InvalidMessage);
+        }
+        Ok(Self {
+            data: unpack_bits::<Zq>(bytes, bytes.len() * 8, order),
+        })
+    }
+
+    /// Deserializes a message from bytes produced by [Message::to_bytes].
+    ///
+    /// Returns [Error::InvalidMessageBytes] if the header is missing, the
+    /// declared length is larger than `N`, or the payload does not match the
+    /// declared length.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_order(bytes, BitOrder::LsbFirst)
+    }
+
+    /// Deserializes a message from bytes produced by [Message::to_bytes_with_order]
+    /// with the same bit order.
+    pub fn from_bytes_with_order(bytes: &[u8], order: BitOrder) -> Result<Self, Error> {
+        if bytes.len() < LENGTH_HEADER_SIZE {
+            return Err(Error::InvalidMessageBytes);
+        }
+        let (header, payload) = bytes.split_at(LENGTH_HEADER_SIZE);
+        let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+        if len > N || payload.len() != len.div_ceil(8) {
+            return Err(Error::InvalidMessageBytes);
+        }
+
+        Ok(Self {
+            data: unpack_bits::<Zq>(payload, len, order),
+        })
+    }
+
+    /// Adds two messages as polynomials over GF(2), i.e. the bitwise XOR. The result has
+    /// the length of the longer message.
+    ///
+    /// It corresponds to the addition of ciphertexts ([CipherText::add_ciphertext]), or of a
+    /// ciphertext and a plaintext ([CipherText::add_plaintext]).
+    ///
+    /// [CipherText::add_ciphertext]: crate::CipherText::add_ciphertext
+    /// [CipherText::add_plaintext]: crate::CipherText::add_plaintext
+    pub fn gf2_add(&self, other: &Self) -> Self {
+        let len = self.data.len().max(other.data.len());
+        let data = (0..len)
+            .map(|i| {
+                let a = self.data.get(i).is_some_and(|mi| mi.is_one());
+                let b = other.data.get(i).is_some_and(|mi| mi.is_one());
+                if a ^ b {
+                    Zq::I::one()
+                } else {
+                    Zq::I::zero()
+                }
+            })
+            .collect();
+        Self { data }
+    }
+
+    /// Multiplies two messages as polynomials over GF(2) modulo `X^N + 1`, the message ring
+    /// reduced modulo 2 (i.e. the ring of binary cyclic codes of length `N`). The result has
+    /// length `N`.
+    ///
+    /// It corresponds to the multiplication of a ciphertext by a plaintext polynomial
+    /// ([CipherText::mul_plaintext]), within the noise limit. The product of two
+    /// ciphertexts is not supported.
+    ///
+    /// [CipherText::mul_plaintext]: crate::CipherText::mul_plaintext
+    pub fn gf2_mul(&self, other: &Self) -> Self {
+        let mut product = vec![false; N];
+        for (i, a) in self.data.iter().enumerate() {
+            if !a.is_one() {
+                continue;
+            }
+            for (j, b) in other.data.iter().enumerate() {
+                if b.is_one() {
+                    // X^N = -1 = 1 over GF(2)
+                    product[(i + j) % N] ^= true;
+                }
+            }
+        }
+        let data = product
+            .into_iter()
+            .map(|bit| if bit { Zq::I::one() } else { Zq::I::zero() })
+            .collect();
         Self { data }
     }
 }
 
+/// The order of the bits within each byte when serializing a [Message].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitOrder {
+    /// The first bit of the message is the least significant bit of the byte.
+    #[default]
+    LsbFirst,
+    /// The first bit of the message is the most significant bit of the byte.
+    MsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the bit position within a byte of the `i`-th bit (`i < 8`).
+    #[inline]
+    fn shift(self, i: usize) -> usize {
+        match self {
+            BitOrder::LsbFirst => i,
+            BitOrder::MsbFirst => 7 - i,
+        }
+    }
+}
+
+/// Checks that the message `m` is binary with length at most `N`, as required by
+/// [Message::try_new] and [EncryptKey::try_encrypt](crate::EncryptKey::try_encrypt).
+pub(crate) fn validate_message<Zq: IntField, const N: usize>(
+    m: &[Zq::I],
+) -> Result<(), EncryptError> {
+    if m.len() > N {
+        return Err(EncryptError::TooLong { len: m.len(), n: N });
+    }
+    match m.iter().position(|mi| !mi.is_zero() && !mi.is_one()) {
+        Some(index) => Err(EncryptError::NonBinary {
+            index,
+            value: Zq::to_word(&m[index]).map_or(i128::MAX, i128::from),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Expands the first `len` bits of `bytes` (at most `8 * bytes.len()`) in the given order.
+fn unpack_bits<Zq: IntField>(bytes: &[u8], len: usize, order: BitOrder) -> Vec<Zq::I> {
+    (0..len)
+        .map(|i| {
+            if (bytes[i / 8] >> order.shift(i % 8)) & 1 == 1 {
+                Zq::I::one()
+            } else {
+                Zq::I::zero()
+            }
+        })
+        .collect()
+}
+
 impl<Zq: IntField, const N: usize> From<Vec<Zq::I>> for Message<Zq, N> {
     fn from(value: Vec<Zq::I>) -> Self {
         Message::new(value)
@@ -0,0 +1,93 @@
+//! Defines the [NoiseTracked] ciphertext which keeps track of its noise bound through
+//! homomorphic operations.
+
+use core::ops::{Add, Mul, Sub};
+use num::{Signed, ToPrimitive};
+
+use crate::{CipherText, IntField};
+
+/// A ciphertext together with a worst-case bound on the infinity norm of its noise.
+///
+/// Decryption is guaranteed to be correct as long as the bound is less than `Q/4`, i.e.
+/// [NoiseTracked::remaining_budget] is positive. The bound is updated by each homomorphic
+/// operation:
+/// - a fresh ciphertext has the bound `2N * B^2 + B`,
+/// - addition sums the bounds,
+/// - multiplication by a scalar `k` multiplies the bound by `|k|`,
+/// - multiplication by a plaintext `p` multiplies the bound by `||p||_1`, the sum of the
+///   absolute coefficients of `p`, which grows much faster than addition.
+///
+/// The multiplications also add the rounding error of `[q/2] * k` which is at most `|k|`
+/// (or `||p||_1`).
+#[derive(Clone, PartialEq)]
+pub struct NoiseTracked<Zq: IntField, const N: usize> {
+    ciphertext: CipherText<Zq, N>,
+    noise_bound: f64,
+}
+
+impl<Zq: IntField, const N: usize> NoiseTracked<Zq, N>
+where
+    Zq::I: ToPrimitive,
+{
+    /// Starts tracking a freshly encrypted ciphertext.
+    pub fn new(ciphertext: CipherText<Zq, N>) -> Self {
+        let b = Zq::B.to_f64().unwrap();
+        Self {
+            ciphertext,
+            noise_bound: 2.0 * N as f64 * b * b + b,
+        }
+    }
+
+    /// Returns the tracked ciphertext.
+    pub fn ciphertext(&self) -> &CipherText<Zq, N> {
+        &self.ciphertext
+    }
+
+    /// Returns the tracked ciphertext, consuming the tracker.
+    pub fn into_inner(self) -> CipherText<Zq, N> {
+        self.ciphertext
+    }
+
+    /// Returns the worst-case bound on the infinity norm of the noise.
+    pub fn noise_bound(&self) -> f64 {
+        self.noise_bound
+    }
+
+    /// Returns `Q/4` minus the noise bound. Decryption is guaranteed to be correct
+    /// while the remaining budget is positive.
+    pub fn remaining_budget(&self) -> f64 {
+        Zq::Q.to_f64().unwrap() / 4.0 - self.noise_bound
+    }
+
+    /// Adds the two ciphertexts, summing their noise bounds.
+    pub fn add(&self, other: &Self) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        Self {
+            ciphertext: self.ciphertext.add_ciphertext(&other.ciphertext),
+            noise_bound: self.noise_bound + other.noise_bound,
+        }
+    }
+
+    /// Multiplies the ciphertext by the integer `k` (see [CipherText::mul_scalar]).
+    pub fn mul_scalar(&self, k: &Zq::I) -> Self {
+        let k_abs = k.abs().to_f64().unwrap();
+        Self {
+            ciphertext: self.ciphertext.mul_scalar(k),
+            noise_bound: k_abs * self.noise_bound + k_abs,
+        }
+    }
+
+    /// Multiplies the ciphertext by the plaintext polynomial `p` (see [CipherText::mul_plaintext]).
+    pub fn mul_plaintext(&self, p: &[Zq::I]) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let p_norm: f64 = p.iter().map(|c| c.abs().to_f64().unwrap()).sum();
+        Self {
+            ciphertext: self.ciphertext.mul_plaintext(p),
+            noise_bound: p_norm * self.noise_bound + p_norm,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Number theoretic transform (NTT) for the multiplication of polynomials in `Zq[x]/(x^N+1)`.
+//!
+//! The negacyclic transform of length `N` exists when `Q` is a prime with `Q = 1 mod 2N`,
+//! i.e. there is a primitive `2N`-th root of unity `psi` in `Zq`.
+
+use alloc::{sync::Arc, vec::Vec};
+#[cfg(feature = "test-utils")]
+use core::ops::{Add, Mul, Sub};
+use poly_ring_xnp1::Polynomial;
+#[cfg(feature = "test-utils")]
+use rand::Rng;
+
+#[cfg(feature = "test-utils")]
+use crate::polynomial::{modulo_coefficients, rand_polynomial};
+use crate::{intfield::IntField, params::is_prime, polynomial::to_fixed_coeffs_vec};
+
+/// The precomputed tables of the negacyclic NTT of length `N` modulo `q`.
+pub(crate) struct NegacyclicNtt<const N: usize> {
+    q: u64,
+    /// `psi^i` for `i` in `[0, N)`.
+    psi_pows: Vec<u64>,
+    /// `psi^-i * N^-1` for `i` in `[0, N)`.
+    psi_inv_pows: Vec<u64>,
+    /// `omega = psi^2`, the primitive `N`-th root of unity.
+    omega: u64,
+    omega_inv: u64,
+}
+
+impl<const N: usize> NegacyclicNtt<N> {
+    /// Returns the NTT tables for the prime modulus `q`, or `None` if `q` is not NTT-friendly.
+    pub(crate) fn new(q: u64) -> Option<Self> {
+        let two_n = 2 * N as u64;
+        if !N.is_power_of_two() || q > u32::MAX as u64 || !is_prime(q) || q % two_n != 1 {
+            return None;
+        }
+
+        // psi = g^((q-1)/2N) is a primitive 2N-th root of unity iff psi^N = -1
+        let psi = (2..q)
+            .map(|g| pow_mod(g, (q - 1) / two_n, q))
+            .find(|&psi| pow_mod(psi, N as u64, q) == q - 1)?;
+        let psi_inv = pow_mod(psi, q - 2, q);
+        let n_inv = pow_mod(N as u64, q - 2, q);
+
+        let powers = |base: u64, init: u64| {
+            (0..N)
+                .scan(init, |acc, _| {
+                    let p = *acc;
+                    *acc = mul_mod(*acc, base, q);
+                    Some(p)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Some(Self {
+            q,
+            psi_pows: powers(psi, 1),
+            psi_inv_pows: powers(psi_inv, n_inv),
+            omega: mul_mod(psi, psi, q),
+            omega_inv: mul_mod(psi_inv, psi_inv, q),
+        })
+    }
+
+    /// Transforms the coefficients (in `[0, q)`) into the NTT domain in place.
+    pub(crate) fn forward(&self, a: &mut [u64]) {
+        a.iter_mut()
+            .zip(self.psi_pows.iter())
+            .for_each(|(ai, p)| *ai = mul_mod(*ai, *p, self.q));
+        cyclic_ntt(a, self.omega, self.q);
+    }
+
+    /// Transforms the NTT domain values back into the coefficients in place.
+    pub(crate) fn inverse(&self, a: &mut [u64]) {
+        cyclic_ntt(a, self.omega_inv, self.q);
+        a.iter_mut()
+            .zip(self.psi_inv_pows.iter())
+            .for_each(|(ai, p)| *ai = mul_mod(*ai, *p, self.q));
+    }
+
+    /// Multiplies the two polynomials with coefficients in `[0, q)`.
+    pub(crate) fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let (mut a, mut b) = (a.to_vec(), b.to_vec());
+        self.forward(&mut a);
+        self.forward(&mut b);
+        a.iter_mut()
+            .zip(b.iter())
+            .for_each(|(ai, bi)| *ai = mul_mod(*ai, *bi, self.q));
+        self.inverse(&mut a);
+        a
+    }
+}
+
+/// Multiplies the two polynomials via the NTT. Returns `None` if `Q` is not NTT-friendly, or
+/// the coefficients cannot be converted with [IntField::to_word].
+pub(crate) fn ntt_mul<Zq: IntField, const N: usize>(
+    a: &Polynomial<Zq::I, N>,
+    b: &Polynomial<Zq::I, N>,
+) -> Option<Polynomial<Zq::I, N>> {
+    let q = u64::try_from(Zq::to_word(&Zq::Q)?).ok()?;
+    let ntt = cached_ntt::<N>(q)?;
+
+    let to_residues = |p: &Polynomial<Zq::I, N>| {
+        to_fixed_coeffs_vec::<Zq, N>(p)
+            .iter()
+            .map(|c| Zq::to_word(c).map(|c| c.rem_euclid(q as i64) as u64))
+            .collect::<Option<Vec<_>>>()
+    };
+    let c = ntt.mul(&to_residues(a)?, &to_residues(b)?);
+
+    let coeffs = c
+        .into_iter()
+        .map(|ci| Zq::from_word(ci as i64).map(|ci| Zq::modulo(&ci)))
+        .collect::<Option<Vec<_>>>()?;
+    Some(Polynomial::new(coeffs))
+}
+
+/// Returns the NTT tables for the modulus `q`, computed once per `(q, N)` and shared by all
+/// the following multiplications.
+#[cfg(feature = "std")]
+fn cached_ntt<const N: usize>(q: u64) -> Option<Arc<NegacyclicNtt<N>>> {
+    use std::{
+        any::Any,
+        collections::BTreeMap,
+        sync::{Mutex, OnceLock, PoisonError},
+    };
+
+    type Cache = Mutex<BTreeMap<(u64, usize), Option<Arc<dyn Any + Send + Sync>>>>;
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    cache
+        .entry((q, N))
+        .or_insert_with(|| {
+            NegacyclicNtt::<N>::new(q).map(|ntt| Arc::new(ntt) as Arc<dyn Any + Send + Sync>)
+        })
+        .clone()
+        .and_then(|ntt| ntt.downcast().ok())
+}
+
+/// Returns the NTT tables for the modulus `q`. Without the `std` feature there is no
+/// global cache, so they are computed on every multiplication.
+#[cfg(not(feature = "std"))]
+fn cached_ntt<const N: usize>(q: u64) -> Option<Arc<NegacyclicNtt<N>>> {
+    NegacyclicNtt::<N>::new(q).map(Arc::new)
+}
+
+/// Multiplies `trials` pairs of random polynomials both with the NTT and with the schoolbook
+/// multiplication, and checks that the products agree.
+///
+/// Returns false on any mismatch, or if `Q` is not NTT-friendly (i.e. `Q` is not a prime
+/// with `Q = 1 mod 2N`), in which case the NTT is not available for the field.
+#[cfg(feature = "test-utils")]
+pub fn verify_ntt_correctness<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+    trials: usize,
+) -> bool
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    (0..trials).all(|_| {
+        let a = rand_polynomial::<Zq, N>(rng);
+        let b = rand_polynomial::<Zq, N>(rng);
+        let expected = modulo_coefficients::<Zq, N>(a.clone() * b.clone());
+        ntt_mul::<Zq, N>(&a, &b).is_some_and(|c| {
+            to_fixed_coeffs_vec::<Zq, N>(&c) == to_fixed_coeffs_vec::<Zq, N>(&expected)
+        })
+    })
+}
+
+/// The iterative Cooley-Tukey cyclic NTT with the primitive `n`-th root of unity `omega`.
+fn cyclic_ntt(a: &mut [u64], omega: u64, q: u64) {
+    let n = a.len();
+    let bits = n.trailing_zeros();
+    if bits == 0 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let w_len = pow_mod(omega, (n / len) as u64, q);
+        for start in (0..n).step_by(len) {
+            let mut w = 1;
+            for k in 0..len / 2 {
+                let x = a[start + k];
+                let y = mul_mod(a[start + k + len / 2], w, q);
+                a[start + k] = (x + y) % q;
+                a[start + k + len / 2] = (x + q - y) % q;
+                w = mul_mod(w, w_len, q);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn mul_mod(a: u64, b: u64, q: u64) -> u64 {
+    ((a as u128 * b as u128) % q as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, q: u64) -> u64 {
+    let mut result = 1;
+    base %= q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, q);
+        }
+        base = mul_mod(base, base, q);
+        exp >>= 1;
+    }
+    result
+}
@@ -0,0 +1,51 @@
+//! Defines the parallel batch encryption with [rayon].
+
+use core::ops::{Add, Mul, Sub};
+use rayon::prelude::*;
+
+use crate::{intfield::IntField, xof::XofRng, CipherText, EncryptKey};
+
+/// The domain separation tag for deriving the randomness of each message in
+/// [EncryptKey::encrypt_batch_par].
+const BATCH_DOMAIN: &[u8] = b"rlwe-encryption/batch";
+
+impl<Zq: IntField, const N: usize> EncryptKey<Zq, N> {
+    /// Encrypts the binary messages in parallel on the rayon thread pool. The randomness of
+    /// the `i`-th message is derived from `seed || i`, so the result is reproducible from the
+    /// seed whatever the number of threads, and in the same order as the messages.
+    ///
+    /// Like a [Session](crate::Session), the seed must be kept secret and never reused,
+    /// because anyone knowing it can recompute the randomness and thus decrypt the messages.
+    ///
+    /// ## Safety
+    /// Each message must be binary with length at most `N`, see [EncryptKey::encrypt_slice].
+    ///
+    /// ```
+    /// let rng = &mut rand::rng();
+    /// let (ek, dk) = rlwe_encryption::standard(rng);
+    ///
+    /// let messages = vec![vec![1, 0, 1], vec![0, 1]];
+    /// let cs = ek.encrypt_batch_par(&messages, [7u8; 32]);
+    /// assert_eq!(dk.decrypt(cs[1].clone())[..2], [0, 1]);
+    /// ```
+    pub fn encrypt_batch_par(
+        &self,
+        messages: &[Vec<Zq::I>],
+        seed: [u8; 32],
+    ) -> Vec<CipherText<Zq, N>>
+    where
+        Zq::I: Send + Sync,
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        messages
+            .par_iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let mut input = [0u8; 40];
+                input[..32].copy_from_slice(&seed);
+                input[32..].copy_from_slice(&(i as u64).to_le_bytes());
+                self.encrypt_slice(&mut XofRng::new(BATCH_DOMAIN, &input), m)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,460 @@
+//! Estimators and utilities for choosing the parameters `N`, `Q` and `B`.
+//!
+//! The estimates are intended for exploring parameter sets, not as a replacement for
+//! a full lattice security analysis.
+
+use alloc::{format, string::String, vec};
+use core::ops::{Add, Mul, Neg, Sub};
+use num::{One, Signed, ToPrimitive};
+use rand::Rng;
+
+use crate::{key_gen_unchecked, Error, IntField, Message, ParamError, StandardZq, ZqI64};
+
+/// The candidate values of `N` searched by [recommend_params].
+const CANDIDATE_N: [usize; 5] = [256, 512, 1024, 2048, 4096];
+
+/// The largest `Q` searched by [recommend_params], so that it fits an `i32` field.
+const MAX_RECOMMENDED_Q: u64 = 1 << 31;
+
+/// A parameter set together with its estimated failure probability and security level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamsManifest {
+    /// The length of the polynomials.
+    pub n: usize,
+    /// The prime modulus.
+    pub q: u64,
+    /// The boundary of the coefficients of the small polynomials.
+    pub b: u64,
+    /// The estimated probability that decryption of a ciphertext fails.
+    pub failure_probability: f64,
+    /// The estimated classical security level in bits.
+    pub security_bits: f64,
+}
+
+/// Checks that the parameters are structurally valid: `N` is a power of 2, `Q` is an
+/// odd prime, and `B` is positive and less than `Q/4`.
+///
+/// Unlike [check_parameters], it does not require the condition `2N * B^2 + B < Q/4`,
+/// e.g. the parameters recommended by [recommend_params] for a target failure probability
+/// may violate it.
+pub fn check_params(n: usize, q: u64, b: u64) -> Result<(), Error> {
+    check_structure(n, q as i128, b as i128).map_err(|_| Error::InvalidParams)?;
+    if n < 2 || q == 2 || !is_prime(q) || b >= q / 4 {
+        return Err(Error::InvalidParams);
+    }
+    Ok(())
+}
+
+/// Checks the parameters of the field `Zq` with the length `N`: `N` is a power of 2, `Q`
+/// and `B` are positive, and the condition `2N * B^2 + B < Q/4` holds for decryption to be
+/// correct. On failure, the [ParamError] carries the offending values, e.g. the computed
+/// left-hand side and right-hand side of the condition.
+///
+/// Unlike [check_params], it takes the parameters from a concrete [IntField], and does not
+/// require `Q` to be a prime. It is intended for users to check their own fields, e.g. in
+/// a test suite. It is also the check of [try_key_gen](crate::try_key_gen) and
+/// [diagnose_params].
+///
+/// The values which do not fit in `i128`, e.g. of a bigint field, are saturated, which
+/// can only make the check stricter.
+///
+/// ```
+/// use rlwe_encryption::{check_parameters, ParamError, StandardZq};
+///
+/// assert!(check_parameters::<StandardZq, 256>().is_ok());
+/// // 2 * 512 * 1^2 + 1 = 1025 is not less than 3329/4 = 832
+/// assert_eq!(
+///     check_parameters::<StandardZq, 512>(),
+///     Err(ParamError::ParametersTooSmall { lhs: 1025, rhs: 832 })
+/// );
+/// ```
+pub fn check_parameters<Zq: IntField, const N: usize>() -> Result<(), ParamError>
+where
+    Zq::I: ToPrimitive,
+{
+    let saturate = |x: &Zq::I| {
+        x.to_i128().unwrap_or(if x.is_negative() {
+            i128::MIN
+        } else {
+            i128::MAX
+        })
+    };
+    check_parameter_values(N, saturate(&Zq::Q), saturate(&Zq::B))
+}
+
+/// Checks the parameters like [check_parameters], but with the values of `N`, `Q` and `B`,
+/// e.g. of a [DynField](crate::DynField). It is the single implementation of the condition
+/// `2N * B^2 + B < Q/4`.
+pub(crate) fn check_parameter_values(n: usize, q: i128, b: i128) -> Result<(), ParamError> {
+    check_structure(n, q, b)?;
+    let lhs = (2 * n as i128)
+        .checked_mul(b)
+        .and_then(|x| x.checked_mul(b))
+        .and_then(|x| x.checked_add(b));
+    // compares 4 * lhs with q, since lhs < q/4 does not hold for the integer division q/4
+    // when q = 4 * lhs + 1, ..., 4 * lhs + 3
+    match lhs {
+        Some(lhs) if lhs.checked_mul(4).is_some_and(|x| x < q) => Ok(()),
+        lhs => Err(ParamError::ParametersTooSmall {
+            lhs: lhs.unwrap_or(i128::MAX),
+            rhs: q / 4,
+        }),
+    }
+}
+
+/// Checks that `N` is a power of 2, and `Q` and `B` are positive.
+fn check_structure(n: usize, q: i128, b: i128) -> Result<(), ParamError> {
+    if !n.is_power_of_two() {
+        return Err(ParamError::NotPowerOfTwo { n });
+    }
+    if q <= 0 {
+        return Err(ParamError::NonPositiveModulus { q });
+    }
+    if b <= 0 {
+        return Err(ParamError::NonPositiveBound { b });
+    }
+    Ok(())
+}
+
+/// Checks the parameters like [check_parameters], and also that `Q` is a prime. On failure, returns [Error::MisconfiguredParams] telling
+/// which condition fails, by how much, and a suggested fix.
+///
+/// ```
+/// use rlwe_encryption::{diagnose_params, Error};
+///
+/// assert!(diagnose_params(256, 3329, 1).is_ok());
+/// assert_eq!(
+///     diagnose_params(256, 100, 1).unwrap_err().to_string(),
+///     "misconfigured parameters: Q=100 too small for N=256, B=1: need Q > 2052, try Q=2053"
+/// );
+/// ```
+pub fn diagnose_params(n: usize, q: u64, b: u64) -> Result<(), Error> {
+    let fail = |msg: String| Err(Error::MisconfiguredParams(msg));
+
+    match check_parameter_values(n, q as i128, b as i128) {
+        Ok(()) => {}
+        Err(ParamError::NotPowerOfTwo { n }) => {
+            // the powers of 2 around N
+            let lower = (n.max(2) + 1).next_power_of_two() / 2;
+            return fail(format!(
+                "N={n} is not a power of 2, try N={lower} or N={}",
+                lower * 2
+            ));
+        }
+        Err(ParamError::NonPositiveBound { b }) => {
+            return fail(format!("B={b} must be positive, try B=1"));
+        }
+        Err(ParamError::NonPositiveModulus { .. } | ParamError::ParametersTooSmall { .. }) => {
+            let hint = match (min_modulus_for(n, b), min_prime_modulus_for(n, b, false)) {
+                (Some(min_q), Some(prime)) => format!("need Q > {}, try Q={prime}", min_q - 1),
+                _ => String::from("no modulus fits in u64"),
+            };
+            return fail(format!("Q={q} too small for N={n}, B={b}: {hint}"));
+        }
+    }
+    if !is_prime(q) {
+        let hint = match (q..=u64::MAX).find(|&q| is_prime(q)) {
+            Some(next_prime) => format!("try Q={next_prime}"),
+            None => String::from("no larger prime fits in u64"),
+        };
+        return fail(format!("Q={q} is not a prime, {hint}"));
+    }
+    Ok(())
+}
+
+/// Returns the smallest modulus `Q` satisfying `2N * B^2 + B < Q/4`, i.e. the noise of a
+/// fresh ciphertext can never exceed `Q/4` and decryption never fails. Returns `None` if it
+/// does not fit in `u64`.
+///
+/// ```
+/// use rlwe_encryption::min_modulus_for;
+///
+/// assert_eq!(min_modulus_for(256, 1), Some(2053));
+/// assert_eq!(min_modulus_for(256, u64::MAX), None);
+/// ```
+pub fn min_modulus_for(n: usize, b: u64) -> Option<u64> {
+    (2 * n as u64)
+        .checked_mul(b)?
+        .checked_mul(b)?
+        .checked_add(b)?
+        .checked_mul(4)?
+        .checked_add(1)
+}
+
+/// Returns the smallest prime modulus `Q` satisfying `2N * B^2 + B < Q/4`. If `ntt_friendly`
+/// is true, `Q` also satisfies `Q = 1 mod 2N`, so that the negacyclic NTT of length `N` exists.
+/// Returns `None` if there is no such `Q` in `u64`.
+///
+/// ```
+/// use rlwe_encryption::min_prime_modulus_for;
+///
+/// assert_eq!(min_prime_modulus_for(256, 1, false), Some(2053));
+/// assert_eq!(min_prime_modulus_for(256, 1, true), Some(7681));
+/// ```
+pub fn min_prime_modulus_for(n: usize, b: u64, ntt_friendly: bool) -> Option<u64> {
+    let step = 2 * n as u64;
+    let min = min_modulus_for(n, b)?;
+    if ntt_friendly {
+        // the smallest q >= min with q = 1 mod 2N
+        let start = (min - 1).div_ceil(step).checked_mul(step)?.checked_add(1)?;
+        (start..=u64::MAX)
+            .step_by(step as usize)
+            .find(|&q| is_prime(q))
+    } else {
+        (min..=u64::MAX).find(|&q| is_prime(q))
+    }
+}
+
+/// Estimates the probability that decryption fails.
+///
+/// The decrypted noise is `e * r + e3 - e2 * s`, where all the polynomials have
+/// coefficients uniform in `[-B, B]`. Each coefficient is approximated as a Gaussian
+/// with variance `2N * sigma^4 + sigma^2` where `sigma^2 = B(B+1)/3`, and the failure
+/// probability is bounded by the Chernoff bound of the noise exceeding `Q/4`, over
+/// all `N` coefficients.
+pub fn estimate_failure_probability(n: usize, q: u64, b: u64) -> f64 {
+    failure_probability(n, q as f64 / 4.0, fresh_noise_variance(n, b))
+}
+
+/// Returns the variance of each coefficient of the noise of a fresh ciphertext.
+fn fresh_noise_variance(n: usize, b: u64) -> f64 {
+    let sigma2 = (b * (b + 1)) as f64 / 3.0;
+    2.0 * n as f64 * sigma2 * sigma2 + sigma2
+}
+
+/// Returns the Chernoff bound of the probability that any of the `n` coefficients, with
+/// variance `noise_var`, exceeds `t` in absolute value.
+fn failure_probability(n: usize, t: f64, noise_var: f64) -> f64 {
+    (n as f64 * 2.0 * libm::exp(-t * t / (2.0 * noise_var))).min(1.0)
+}
+
+/// The failure probability tolerated by [max_homomorphic_additions].
+const ADDITION_FAILURE_TARGET: f64 = 1.0 / (1u64 << 40) as f64;
+
+/// Returns the maximum number of fresh ciphertexts which can be summed up (see
+/// [CipherText::add_ciphertext](crate::CipherText::add_ciphertext)) while the estimated
+/// decryption failure probability stays below `2^-40`.
+///
+/// The sum of `k` ciphertexts has noise variance `k` times of a fresh one (see
+/// [estimate_failure_probability]), plus a rounding error of up to `k/2` from `[q/2] * k`.
+/// Returns 0 if even a fresh ciphertext exceeds the target.
+///
+/// ```
+/// use rlwe_encryption::{max_homomorphic_additions, StandardZq};
+///
+/// assert!(max_homomorphic_additions::<StandardZq, 256>() > 1);
+/// ```
+pub fn max_homomorphic_additions<Zq: IntField, const N: usize>() -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    let q = Zq::Q.to_f64().unwrap();
+    let var = fresh_noise_variance(N, Zq::B.to_u64().unwrap());
+    let within_target = |k: usize| {
+        let t = q / 4.0 - k as f64 / 2.0;
+        t > 0.0 && failure_probability(N, t, k as f64 * var) <= ADDITION_FAILURE_TARGET
+    };
+    (1..).take_while(|&k| within_target(k)).last().unwrap_or(0)
+}
+
+/// The result of [stress_test_field].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StressReport {
+    /// The number of encrypt/decrypt cycles run.
+    pub cycles: usize,
+    /// The number of cycles in which the decrypted message differs from the original one.
+    pub failures: usize,
+    /// The total number of wrongly decrypted bits over all the cycles.
+    pub bit_errors: usize,
+}
+
+impl StressReport {
+    /// Returns true if no decryption failed.
+    pub fn passed(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+/// Runs `trials` rounds of encrypt/decrypt cycles to empirically validate the field `Zq`
+/// with `N`, complementing the analytical [check_params]. Each round generates a fresh key
+/// pair, and encrypts both a random message and the worst-case all-ones message of length `N`.
+///
+/// ```
+/// use rlwe_encryption::{stress_test_field, StandardZq};
+///
+/// let report = stress_test_field::<StandardZq, 256>(&mut rand::rng(), 10);
+/// assert_eq!(report.cycles, 20);
+/// assert!(report.passed());
+/// ```
+pub fn stress_test_field<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+    trials: usize,
+) -> StressReport
+where
+    for<'a> &'a Zq::I:
+        Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+{
+    let mut report = StressReport {
+        cycles: 0,
+        failures: 0,
+        bit_errors: 0,
+    };
+    for _ in 0..trials {
+        let (ek, dk) = key_gen_unchecked::<Zq, N>(rng);
+        let random = Message::<Zq, N>::random(rng, N);
+        let all_ones = Message::<Zq, N>::new(vec![Zq::I::one(); N]);
+
+        for m in [random, all_ones] {
+            let expected = m.data.clone();
+            let decrypted = dk.decrypt(ek.encrypt(rng, m));
+            let bit_errors = expected
+                .iter()
+                .zip(decrypted.iter())
+                .filter(|(a, b)| a != b)
+                .count();
+
+            report.cycles += 1;
+            report.bit_errors += bit_errors;
+            if bit_errors > 0 {
+                report.failures += 1;
+            }
+        }
+    }
+    report
+}
+
+/// Estimates the classical security level in bits of the ring-LWE instance.
+///
+/// The root Hermite factor `delta` needed by a distinguishing attack is
+/// `log2(delta) = log2(Q/sigma)^2 / (4N * log2(Q))`, the BKZ block size `beta` achieving
+/// `delta` is searched, and the cost is given by the core-SVP model `0.292 * beta`.
+pub fn estimate_security_bits(n: usize, q: u64, b: u64) -> f64 {
+    let sigma = libm::sqrt((b * (b + 1)) as f64 / 3.0);
+    let log_q = libm::log2(q as f64);
+    let log_delta = libm::pow(log_q - libm::log2(sigma), 2.0) / (4.0 * n as f64 * log_q);
+
+    let mut beta = 50.0_f64;
+    while beta < n as f64 * 2.0 && bkz_log_delta(beta) > log_delta {
+        beta += 1.0;
+    }
+    0.292 * beta
+}
+
+/// Returns the log2 of the root Hermite factor achieved by BKZ with block size `beta`.
+fn bkz_log_delta(beta: f64) -> f64 {
+    let pi_e = 2.0 * core::f64::consts::PI * core::f64::consts::E;
+    libm::log2(libm::pow(core::f64::consts::PI * beta, 1.0 / beta) * beta / pi_e)
+        / (2.0 * (beta - 1.0))
+}
+
+/// Recommends the smallest `N` and prime `Q` (with `B = 1`) such that the estimated
+/// failure probability is at most `target_failure` and the estimated security level is
+/// at least `security_bits`. Returns `None` if no candidate with `N <= 4096` and
+/// `Q <= 2^31` satisfies both, including when `target_failure` is not positive or is NaN.
+///
+/// ```
+/// use rlwe_encryption::{check_params, recommend_params};
+///
+/// let params = recommend_params(2f64.powi(-40), 100.0).unwrap();
+/// assert!(check_params(params.n, params.q, params.b).is_ok());
+/// ```
+pub fn recommend_params(target_failure: f64, security_bits: f64) -> Option<ParamsManifest> {
+    if target_failure.is_nan() || target_failure <= 0.0 {
+        return None;
+    }
+    let b = 1;
+    CANDIDATE_N.iter().find_map(|&n| {
+        // the failure probability decreases with Q while the security increases with N,
+        // so the smallest Q meeting the failure target gives the best security for N.
+        let q = (4 * b + 1..=MAX_RECOMMENDED_Q)
+            .step_by(2)
+            .find(|&q| estimate_failure_probability(n, q, b) <= target_failure && is_prime(q))?;
+        let security = estimate_security_bits(n, q, b);
+        (security >= security_bits).then(|| ParamsManifest {
+            n,
+            q,
+            b,
+            failure_probability: estimate_failure_probability(n, q, b),
+            security_bits: security,
+        })
+    })
+}
+
+/// A marker trait implemented only for the fields `Zq` and lengths `N` which are checked
+/// at compile time to satisfy `2N * B^2 + B < Q/4`, i.e. decryption never fails.
+///
+/// The trait is sealed and implemented for the pre-defined parameter sets. It is used as
+/// a bound by [key_gen_checked](crate::key_gen_checked), so an unsafe `N` is a compile error:
+///
+/// ```compile_fail
+/// // 2 * 512 + 1 >= 3329 / 4
+/// let (ek, dk) = rlwe_encryption::key_gen_checked::<rlwe_encryption::StandardZq, 512>(&mut rand::rng());
+/// ```
+pub trait ValidParams<const N: usize>: IntField + sealed::Sealed<N> {}
+
+mod sealed {
+    pub trait Sealed<const N: usize> {}
+}
+
+/// Implements [ValidParams] for a field with primitive integer type, after asserting the
+/// parameter condition at compile time.
+macro_rules! impl_valid_params {
+    ($zq:ty, $n:expr) => {
+        const _: () = {
+            let b = <$zq as IntField>::B as i128;
+            let q = <$zq as IntField>::Q as i128;
+            assert!(2 * $n * b * b + b < q / 4);
+        };
+        impl sealed::Sealed<$n> for $zq {}
+        impl ValidParams<$n> for $zq {}
+    };
+}
+
+impl_valid_params!(StandardZq, 256);
+impl_valid_params!(ZqI64, 1024);
+
+/// The bases of the Miller-Rabin test, i.e. the first 12 primes, which are enough to make
+/// it deterministic for all `u64`.
+const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Deterministic primality test by the Miller-Rabin test with [MILLER_RABIN_BASES].
+pub(crate) fn is_prime(x: u64) -> bool {
+    if x < 2 {
+        return false;
+    }
+    if let Some(&p) = MILLER_RABIN_BASES.iter().find(|&&p| x.is_multiple_of(p)) {
+        return x == p;
+    }
+    // x - 1 = d * 2^r with d odd
+    let r = (x - 1).trailing_zeros();
+    let d = (x - 1) >> r;
+    MILLER_RABIN_BASES.iter().all(|&a| {
+        let mut y = pow_mod(a, d, x);
+        if y == 1 || y == x - 1 {
+            return true;
+        }
+        (1..r).any(|_| {
+            y = mul_mod(y, y, x);
+            y == x - 1
+        })
+    })
+}
+
+/// Computes `a * b mod m` without overflow.
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Computes `base^exp mod m` by square-and-multiply.
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
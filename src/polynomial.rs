@@ -1,13 +1,19 @@
 //! Auxiliary functions for polynomial operations.
 
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Mul, Sub};
 use num::{
     traits::{One, Zero},
-    Integer, Signed,
+    FromPrimitive, Integer, Signed, ToPrimitive,
 };
 use poly_ring_xnp1::Polynomial;
 use rand::{distr::uniform::SampleUniform, Rng};
 
-use crate::IntField;
+use crate::{
+    intfield::{IntField, Reducer, Reduction},
+    ntt::ntt_mul,
+    DecodeError,
+};
 
 #[inline]
 pub(crate) fn rand_polynomial<Zq: IntField, const N: usize>(
@@ -63,11 +69,36 @@ pub(crate) fn scale_coefficients<Zq: IntField, const N: usize>(
 pub(crate) fn round_coefficients<Zq: IntField, const N: usize>(
     p: Polynomial<Zq::I, N>,
 ) -> Polynomial<Zq::I, N> {
-    let two = Zq::I::one() + Zq::I::one();
-    let q_div_4 = closest_integer_div_two(Zq::Q) / two;
+    round_coefficients_with_threshold::<Zq, N>(p, &Zq::decrypt_threshold())
+}
+
+/// Converts each coefficient `c` of the polynomial to 1 if `|c| > threshold`, or to 0
+/// otherwise. [round_coefficients] uses the threshold `q/4`.
+///
+/// With the `constant-time` feature, it uses [round_coefficients_ct] instead, which has no
+/// data-dependent branch, unless the threshold cannot be converted with
+/// [IntField::to_word] (e.g. for bigint fields, or fields not implementing it).
+pub(crate) fn round_coefficients_with_threshold<Zq: IntField, const N: usize>(
+    p: Polynomial<Zq::I, N>,
+    threshold: &Zq::I,
+) -> Polynomial<Zq::I, N> {
+    match Zq::to_word(threshold) {
+        Some(threshold) if cfg!(feature = "constant-time") => {
+            round_coefficients_ct::<Zq, N>(p, threshold)
+        }
+        _ => round_coefficients_branching::<Zq, N>(p, threshold),
+    }
+}
+
+/// Rounds the coefficients like [round_coefficients_with_threshold] by comparing `|c|`
+/// with the threshold in a conditional branch.
+fn round_coefficients_branching<Zq: IntField, const N: usize>(
+    p: Polynomial<Zq::I, N>,
+    threshold: &Zq::I,
+) -> Polynomial<Zq::I, N> {
     let mut p = p;
     p.coeffs_mut(|c| {
-        *c = if c.abs().gt(&q_div_4) {
+        *c = if c.abs().gt(threshold) {
             Zq::I::one()
         } else {
             Zq::I::zero()
@@ -76,13 +107,81 @@ pub(crate) fn round_coefficients<Zq: IntField, const N: usize>(
     p
 }
 
-/// Applies modulo q to each coefficient of the polynomial.
+/// Rounds the coefficients like [round_coefficients_with_threshold], computing the
+/// comparison of `|c|` with the threshold arithmetically with sign masks on `i64`, without
+/// a conditional branch.
+///
+/// Only the comparison is hardened. The conversions between `I` and `i64` and the
+/// arithmetic of the type `I` (from the `num` traits) are not guaranteed to be
+/// constant-time.
+fn round_coefficients_ct<Zq: IntField, const N: usize>(
+    p: Polynomial<Zq::I, N>,
+    threshold: i64,
+) -> Polynomial<Zq::I, N> {
+    let mut p = p;
+    p.coeffs_mut(|c| {
+        // a coefficient which does not fit is larger than any threshold which does
+        let x = Zq::to_word(c).unwrap_or(i64::MAX);
+        let sign = x >> 63; // all ones if negative
+        let abs = (x ^ sign) - sign;
+        // threshold - |x| is negative, i.e. its sign bit is set, iff |x| > threshold
+        let bit = ((threshold - abs) >> 63) & 1;
+        *c = Zq::from_word(bit).unwrap_or_else(|| {
+            if bit == 1 {
+                Zq::I::one()
+            } else {
+                Zq::I::zero()
+            }
+        });
+    });
+    p
+}
+
+/// Compares the coefficients of the two polynomials, padded to `N`, without an early exit,
+/// i.e. the result agrees with `==` but the running time does not depend on the position
+/// of the first difference.
+///
+/// Only the comparison is hardened, like [round_coefficients_ct]. The coefficients which
+/// cannot be converted with [IntField::to_word], e.g. of bigint fields, are compared with
+/// `==` instead.
+#[cfg(feature = "constant-time")]
+pub(crate) fn ct_eq_polynomials<Zq: IntField, const N: usize>(
+    a: &Polynomial<Zq::I, N>,
+    b: &Polynomial<Zq::I, N>,
+) -> subtle::Choice {
+    use subtle::ConstantTimeEq;
+
+    to_fixed_coeffs_vec::<Zq, N>(a)
+        .iter()
+        .zip(to_fixed_coeffs_vec::<Zq, N>(b).iter())
+        .fold(subtle::Choice::from(1), |acc, (x, y)| {
+            let eq = match (Zq::to_word(x), Zq::to_word(y)) {
+                (Some(x), Some(y)) => x.ct_eq(&y),
+                _ => subtle::Choice::from((x == y) as u8),
+            };
+            acc & eq
+        })
+}
+
+/// Applies modulo q to each coefficient of the polynomial, with the reduction algorithm
+/// selected by [IntField::REDUCTION].
 #[inline]
 pub(crate) fn modulo_coefficients<Zq: IntField, const N: usize>(
     p: Polynomial<Zq::I, N>,
 ) -> Polynomial<Zq::I, N> {
     let mut p = p;
-    p.coeffs_mut(|c| *c = Zq::modulo(c));
+    match Zq::REDUCTION {
+        Reduction::Modulo if cfg!(feature = "simd") && Zq::MODULO_SLICE => {
+            let mut coeffs = p.iter().cloned().collect::<Vec<_>>();
+            Zq::modulo_slice(&mut coeffs);
+            p = Polynomial::new(coeffs);
+        }
+        Reduction::Modulo => p.coeffs_mut(|c| *c = Zq::modulo(c)),
+        _ => {
+            let reducer = Reducer::<Zq>::new();
+            p.coeffs_mut(|c| *c = reducer.reduce(c));
+        }
+    }
     p
 }
 
@@ -92,6 +191,37 @@ pub(crate) fn closest_integer_div_two<I: Integer + Clone>(x: I) -> I {
     x.div_ceil(&(I::one() + I::one()))
 }
 
+/// Multiplies the two polynomials and reduces the coefficients. The negacyclic NTT is used if
+/// it is enabled by [IntField::NTT] and `Q` is NTT-friendly for `N`, otherwise the schoolbook
+/// multiplication.
+pub(crate) fn mul_polynomials<Zq: IntField, const N: usize>(
+    a: &Polynomial<Zq::I, N>,
+    b: &Polynomial<Zq::I, N>,
+) -> Polynomial<Zq::I, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    if Zq::NTT {
+        if let Some(c) = ntt_mul::<Zq, N>(a, b) {
+            return c;
+        }
+    }
+    modulo_coefficients::<Zq, N>(a.clone() * b.clone())
+}
+
+/// Subtracts the polynomial `b` from `a` and reduces the coefficients. Unlike the [Sub] of
+/// [Polynomial], it does not require `&I: Neg`, a bound which breaks the inference of `Zq`
+/// in the callers of generic functions when `ndarray` is linked.
+pub(crate) fn sub_polynomials<Zq: IntField, const N: usize>(
+    a: Polynomial<Zq::I, N>,
+    b: Polynomial<Zq::I, N>,
+) -> Polynomial<Zq::I, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    modulo_coefficients::<Zq, N>(a + b.mapv(|x| Zq::I::zero() - x.clone()))
+}
+
 /// Converts a polynomial to a vector of fixed size (N) by padding with zeros.
 #[inline]
 pub(crate) fn to_fixed_coeffs_vec<Zq: IntField, const N: usize>(
@@ -99,11 +229,148 @@ pub(crate) fn to_fixed_coeffs_vec<Zq: IntField, const N: usize>(
 ) -> Vec<Zq::I> {
     let mut p_vec = p.iter().cloned().collect::<Vec<Zq::I>>();
     if p_vec.len() < N {
-        p_vec.extend(std::iter::repeat(Zq::I::zero()).take(N - p_vec.len()));
+        p_vec.extend(core::iter::repeat_n(Zq::I::zero(), N - p_vec.len()));
     }
     p_vec
 }
 
+/// Returns the number of bits needed to represent an integer in the range `[0, q)`.
+#[inline]
+pub(crate) fn coefficient_bit_width<Zq: IntField>() -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    let q_minus_1 = (Zq::Q - Zq::I::one()).to_u64().unwrap();
+    (u64::BITS - q_minus_1.leading_zeros()) as usize
+}
+
+/// Returns the number of bytes of `count` polynomials packed by [pack_coefficients].
+#[inline]
+pub(crate) fn packed_size<Zq: IntField, const N: usize>(count: usize) -> usize
+where
+    Zq::I: ToPrimitive,
+{
+    (count * N * coefficient_bit_width::<Zq>()).div_ceil(8)
+}
+
+/// Packs the coefficients of the polynomials into bytes. Each polynomial is padded to
+/// `N` coefficients, and each coefficient is mapped to the range `[0, q)` and then
+/// written with [coefficient_bit_width] bits in LSB-first order.
+pub(crate) fn pack_coefficients<Zq: IntField, const N: usize>(
+    polys: &[&Polynomial<Zq::I, N>],
+) -> Vec<u8>
+where
+    Zq::I: ToPrimitive,
+{
+    let width = coefficient_bit_width::<Zq>();
+    let mut bytes = vec![0u8; packed_size::<Zq, N>(polys.len())];
+    let mut pos = 0;
+    for p in polys {
+        for c in to_fixed_coeffs_vec::<Zq, N>(p) {
+            let x = c.mod_floor(&Zq::Q).to_u64().unwrap();
+            for i in 0..width {
+                bytes[pos / 8] |= (((x >> i) & 1) as u8) << (pos % 8);
+                pos += 1;
+            }
+        }
+    }
+    bytes
+}
+
+/// Writes the coefficients of the polynomials into bytes. Each polynomial is padded to `N`
+/// coefficients, and each coefficient is written in the symmetric representation as a
+/// signed (two's complement) little-endian integer of `size_of::<I>()` bytes.
+pub(crate) fn to_fixed_width_bytes<Zq: IntField, const N: usize>(
+    polys: &[&Polynomial<Zq::I, N>],
+) -> Vec<u8>
+where
+    Zq::I: ToPrimitive,
+{
+    let width = core::mem::size_of::<Zq::I>();
+    let mut bytes = Vec::with_capacity(polys.len() * N * width);
+    for p in polys {
+        for c in to_fixed_coeffs_vec::<Zq, N>(p) {
+            bytes.extend_from_slice(&c.to_i128().unwrap().to_le_bytes()[..width]);
+        }
+    }
+    bytes
+}
+
+/// Reads `count` polynomials from the bytes produced by [to_fixed_width_bytes]. Each
+/// coefficient must be reduced, i.e. unchanged by [IntField::modulo].
+pub(crate) fn from_fixed_width_bytes<Zq: IntField, const N: usize>(
+    bytes: &[u8],
+    count: usize,
+) -> Result<Vec<Polynomial<Zq::I, N>>, DecodeError>
+where
+    Zq::I: FromPrimitive,
+{
+    let width = core::mem::size_of::<Zq::I>();
+    let expected = count * N * width;
+    if bytes.len() != expected {
+        return Err(DecodeError::WrongLength {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    let coeffs = bytes
+        .chunks_exact(width)
+        .enumerate()
+        .map(|(index, chunk)| {
+            // sign extension
+            let mut buf = if chunk[width - 1] & 0x80 == 0 {
+                [0u8; 16]
+            } else {
+                [0xffu8; 16]
+            };
+            buf[..width].copy_from_slice(chunk);
+            Zq::I::from_i128(i128::from_le_bytes(buf))
+                .filter(|c| Zq::modulo(c) == *c)
+                .ok_or(DecodeError::InvalidCoefficient { index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(coeffs
+        .chunks(N)
+        .map(|p| Polynomial::new(p.to_vec()))
+        .collect())
+}
+
+/// Unpacks `count` polynomials from the bytes produced by [pack_coefficients]. Returns
+/// `None` if the length of the bytes does not match or a coefficient is not less than q.
+pub(crate) fn unpack_coefficients<Zq: IntField, const N: usize>(
+    bytes: &[u8],
+    count: usize,
+) -> Option<Vec<Polynomial<Zq::I, N>>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+{
+    let width = coefficient_bit_width::<Zq>();
+    if bytes.len() != packed_size::<Zq, N>(count) {
+        return None;
+    }
+
+    let q = Zq::Q.to_u64().unwrap();
+    let mut pos = 0;
+    let mut polys = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut coeffs = Vec::with_capacity(N);
+        for _ in 0..N {
+            let mut x = 0u64;
+            for i in 0..width {
+                x |= (((bytes[pos / 8] >> (pos % 8)) & 1) as u64) << i;
+                pos += 1;
+            }
+            if x >= q {
+                return None;
+            }
+            coeffs.push(Zq::modulo(&Zq::I::from_u64(x)?));
+        }
+        polys.push(Polynomial::new(coeffs));
+    }
+    Some(polys)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -123,6 +390,14 @@ mod tests {
                 a
             }
         }
+
+        fn to_word(x: &Self::I) -> Option<i64> {
+            Some(i64::from(*x))
+        }
+
+        fn from_word(x: i64) -> Option<Self::I> {
+            i32::try_from(x).ok()
+        }
     }
 
     #[test]
@@ -153,4 +428,75 @@ mod tests {
         let coeffs = p.iter().cloned().collect::<Vec<i32>>();
         assert_eq!(coeffs, [-2, 1, 0, -1]);
     }
+
+    /// The prime 7681 = 1 mod 512 is NTT-friendly for N = 256.
+    struct ZqI32Q7681;
+
+    impl IntField for ZqI32Q7681 {
+        type I = i32;
+        const Q: i32 = 7681;
+        const B: i32 = 1;
+        const NTT: bool = true;
+        fn modulo(x: &Self::I) -> Self::I {
+            let a = x.rem_euclid(Self::Q);
+            if a > Self::Q / 2 {
+                a - Self::Q
+            } else {
+                a
+            }
+        }
+
+        fn to_word(x: &Self::I) -> Option<i64> {
+            Some(i64::from(*x))
+        }
+
+        fn from_word(x: i64) -> Option<Self::I> {
+            i32::try_from(x).ok()
+        }
+    }
+
+    #[test]
+    fn test_mul_polynomials_ntt() {
+        type Zq = ZqI32Q7681;
+
+        let rng = &mut rand::rng();
+        for _ in 0..5 {
+            let a = rand_polynomial::<Zq, 256>(rng);
+            let b = rand_polynomial::<Zq, 256>(rng);
+            let expected = modulo_coefficients::<Zq, 256>(a.clone() * b.clone());
+            assert!(ntt_mul::<Zq, 256>(&a, &b).is_some());
+            let actual = mul_polynomials::<Zq, 256>(&a, &b);
+            assert_eq!(
+                to_fixed_coeffs_vec::<Zq, 256>(&actual),
+                to_fixed_coeffs_vec::<Zq, 256>(&expected)
+            );
+        }
+
+        // 7681 != 1 mod 2048, so it falls back to the schoolbook multiplication
+        let a = rand_polynomial::<Zq, 1024>(rng);
+        let b = small_polynomial::<Zq, 1024>(rng);
+        assert!(ntt_mul::<Zq, 1024>(&a, &b).is_none());
+        let actual = mul_polynomials::<Zq, 1024>(&a, &b);
+        let expected = modulo_coefficients::<Zq, 1024>(a * b);
+        assert_eq!(
+            to_fixed_coeffs_vec::<Zq, 1024>(&actual),
+            to_fixed_coeffs_vec::<Zq, 1024>(&expected)
+        );
+    }
+
+    #[test]
+    fn test_round_coefficients_ct() {
+        // every coefficient value, reduced or not, with q = 7, padded with zeros to N = 32
+        let mut values = (-14..=14).collect::<Vec<i32>>();
+        values.resize(32, 0);
+        let p = Polynomial::<i32, 32>::new(values);
+        // q/4 = 2 for q = 7
+        let expected = round_coefficients_branching::<ZqI32Q7, 32>(p.clone(), &2);
+        let actual = round_coefficients_ct::<ZqI32Q7, 32>(p, 2);
+        assert_eq!(
+            to_fixed_coeffs_vec::<ZqI32Q7, 32>(&actual),
+            to_fixed_coeffs_vec::<ZqI32Q7, 32>(&expected)
+        );
+        assert!(actual.iter().any(|c| *c == 1));
+    }
 }
@@ -0,0 +1,586 @@
+//! Defines a non-interactive zero-knowledge proof that a ciphertext decrypts to a claimed
+//! message, without revealing the secret.
+//!
+//! The proof is a sigma protocol made non-interactive with the Fiat-Shamir transform, with
+//! rejection sampling ("Fiat-Shamir with aborts") so that the responses do not leak the
+//! secret. The prover shows knowledge of small `s`, `e` and `n` with
+//! - `t = a * s + e`, i.e. `s` is the secret of the encryption key `(a, t)`, and
+//! - `v - [q/2] m = u * s + n`, i.e. the ciphertext `(u, v)` decrypts to `m` with noise `n`.
+//!
+//! The same technique gives a proof that a ciphertext encrypts a single bit (see [prove_bit]),
+//! as an OR composition of the proofs of knowledge of the encryption randomness for the
+//! plaintexts `0` and `1`.
+//!
+//! The relation with `v - [q/2] m` replaced by a partial decryption `d` proves the partial
+//! decryptions of the threshold decryption (see
+//! [combine_partials_verified](crate::combine_partials_verified)).
+
+use alloc::vec;
+use core::ops::{Add, Mul, Sub};
+use num::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use poly_ring_xnp1::Polynomial;
+use rand::{Rng, RngCore};
+
+use crate::{
+    encrypt::Opening,
+    polynomial::{
+        modulo_coefficients, mul_polynomials, pack_coefficients, rand_polynomial_within,
+        scale_coefficients, sub_polynomials,
+    },
+    xof::XofRng,
+    CipherText, DecryptKey, EncryptKey, IntField, Message,
+};
+
+/// The number of nonzero coefficients (each `1` or `-1`) of the challenge polynomial.
+const CHALLENGE_WEIGHT: usize = 4;
+
+/// The maximum number of attempts of the rejection sampling in [prove_decryption] and
+/// [prove_bit].
+pub const MAX_PROOF_RETRIES: usize = 1000;
+
+/// The domain separation tag for hashing the statement and the commitment.
+const PROOF_DOMAIN: &[u8] = b"rlwe-encryption/decryption-proof";
+/// The domain separation tag for expanding the hash into the challenge polynomial.
+const CHALLENGE_DOMAIN: &[u8] = b"rlwe-encryption/decryption-proof-challenge";
+/// The domain separation tag for hashing the statement and the commitments of [BitProof].
+const BIT_PROOF_DOMAIN: &[u8] = b"rlwe-encryption/bit-proof";
+/// The domain separation tag for hashing the statement and the commitment of a partial
+/// decryption.
+const PARTIAL_DECRYPTION_DOMAIN: &[u8] = b"rlwe-encryption/partial-decryption-proof";
+
+/// A proof that a ciphertext decrypts to a claimed message, produced by [prove_decryption]
+/// and checked by [verify_decryption].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptionProof<Zq: IntField, const N: usize> {
+    pub(crate) challenge: [u8; 32],
+    pub(crate) z_s: Polynomial<Zq::I, N>,
+    pub(crate) z_e: Polynomial<Zq::I, N>,
+    pub(crate) z_n: Polynomial<Zq::I, N>,
+}
+
+/// Proves that the ciphertext `c` decrypts to `claimed_m` with the decryption key `dk` of
+/// the encryption key `ek`, without revealing the secret. The message is padded with zeros
+/// to `N` bits, as returned by [DecryptKey::decrypt].
+///
+/// Returns `None` if no proof can be produced: the decryption noise `v - [q/2] m - u * s`
+/// must be at most `Q / 32N`, so that the responses fit below `Q/8`, and the rejection
+/// sampling must succeed within [MAX_PROOF_RETRIES] attempts. It requires a large modulus,
+/// e.g. [ZqI64](crate::ZqI64), while it always fails with [StandardZq](crate::StandardZq).
+/// If `claimed_m` is not the decryption of `c`, the noise is about `Q/2` and it fails too.
+///
+/// ## Soundness
+/// The verifier accepts the responses of the noise up to about `Q/8`, while the decryption
+/// threshold is `Q/4`. A noise extracted from two accepted proofs (the difference of the
+/// responses) is only bounded by `Q/4` itself, so the proof guarantees the decryption to
+/// `claimed_m` with no margin, unlike the honest noise of at most `Q / 32N`.
+///
+/// ```
+/// use rlwe_encryption::{prove_decryption, standard_i64, verify_decryption, Message};
+///
+/// let rng = &mut rand::rng();
+/// let (ek, dk) = standard_i64(rng);
+/// let m = Message::new(vec![1, 0, 1]);
+/// let c = ek.encrypt(rng, m.clone());
+///
+/// let proof = prove_decryption(&ek, &dk, &c, &m, rng).unwrap();
+/// assert!(verify_decryption(&ek, &c, &m, &proof));
+/// ```
+pub fn prove_decryption<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    dk: &DecryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    claimed_m: &Message<Zq, N>,
+    rng: &mut impl Rng,
+) -> Option<DecryptionProof<Zq, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    prove_relation(
+        ek,
+        &dk.s,
+        &c.u,
+        &shifted_v(c, claimed_m),
+        |w1, w2| challenge_hash(ek, c, claimed_m, w1, w2),
+        rng,
+    )
+}
+
+/// Verifies the proof produced by [prove_decryption] that the ciphertext `c` under the
+/// encryption key `ek` decrypts to `claimed_m`.
+pub fn verify_decryption<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    claimed_m: &Message<Zq, N>,
+    proof: &DecryptionProof<Zq, N>,
+) -> bool
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    c.is_well_formed()
+        && verify_relation(ek, &c.u, &shifted_v(c, claimed_m), proof, |w1, w2| {
+            challenge_hash(ek, c, claimed_m, w1, w2)
+        })
+}
+
+/// Proves that `d` is a partial decryption `u * s + n` of the ciphertext `c` with the share
+/// `s` committed to by `ek`, for [partial_decrypt](crate::partial_decrypt).
+pub(crate) fn prove_partial_decryption<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    dk: &DecryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    d: &Polynomial<Zq::I, N>,
+    rng: &mut impl Rng,
+) -> Option<DecryptionProof<Zq, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    prove_relation(
+        ek,
+        &dk.s,
+        &c.u,
+        d,
+        |w1, w2| partial_challenge_hash(ek, c, d, w1, w2),
+        rng,
+    )
+}
+
+/// Verifies the proof produced by [prove_partial_decryption].
+pub(crate) fn verify_partial_decryption<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    d: &Polynomial<Zq::I, N>,
+    proof: &DecryptionProof<Zq, N>,
+) -> bool
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    c.is_well_formed()
+        && verify_relation(ek, &c.u, d, proof, |w1, w2| {
+            partial_challenge_hash(ek, c, d, w1, w2)
+        })
+}
+
+/// A proof that a ciphertext encrypts the message `[0]` or `[1]`, i.e. a single bit, produced
+/// by [prove_bit] and checked by [verify_bit]. It does not reveal which one.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitProof<Zq: IntField, const N: usize> {
+    /// The transcripts for the bits `0` and `1`. One of them is simulated.
+    pub(crate) branches: [BitProofBranch<Zq::I, N>; 2],
+}
+
+/// The transcript of the proof of knowledge of the encryption randomness `(r, e2, e3)` for
+/// one of the two bits.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "I: serde::Serialize",
+        deserialize = "I: serde::Deserialize<'de> + Zero"
+    ))
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BitProofBranch<I, const N: usize> {
+    pub(crate) challenge: [u8; 32],
+    pub(crate) z_r: Polynomial<I, N>,
+    pub(crate) z_e2: Polynomial<I, N>,
+    pub(crate) z_e3: Polynomial<I, N>,
+}
+
+/// Proves that the ciphertext `c` encrypts a single bit, i.e. the message `[0]` or `[1]`,
+/// given the `opening` returned by [EncryptKey::encrypt_with_opening], without revealing the
+/// bit, e.g. for a voter to show that the vote is valid.
+///
+/// The prover shows knowledge of small `(r, e2, e3)` with `u = a * r + e2` and
+/// `v - [q/2] b = t * r + e3` for `b = 0` or `b = 1`. The branch of the other bit is
+/// simulated by choosing its challenge first, and the challenges of the two branches must
+/// XOR to the hash of the statement and both commitments, so that only one of them can be
+/// chosen by the prover.
+///
+/// Returns `None` if the opening does not open `c` to `[0]` or `[1]` (see
+/// [EncryptKey::verify_opening]), or if the rejection sampling fails [MAX_PROOF_RETRIES]
+/// times.
+///
+/// ## Soundness
+/// Like [prove_decryption], the extracted randomness is only bounded by the responses,
+/// about `4N * B`, instead of `B`. The ciphertext then decrypts to the proven bit if the
+/// noise of the relaxed randomness stays below `Q/4`. In the worst case it does not for the
+/// presets of this crate, so the guarantee is heuristic and grows with `Q / (N^2 * B^2)`.
+///
+/// ```
+/// use rlwe_encryption::{prove_bit, standard, verify_bit, Message};
+///
+/// let rng = &mut rand::rng();
+/// let (ek, _) = standard(rng);
+/// let (c, opening) = ek.encrypt_with_opening(rng, Message::new(vec![1]));
+///
+/// let proof = prove_bit(&ek, &c, &opening, rng).unwrap();
+/// assert!(verify_bit(&ek, &c, &proof));
+/// ```
+pub fn prove_bit<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    opening: &Opening<Zq, N>,
+    rng: &mut impl Rng,
+) -> Option<BitProof<Zq, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let bit = [false, true]
+        .into_iter()
+        .find(|&b| ek.verify_opening(c, bit_message(b), opening))?;
+    let (real, simulated) = (usize::from(bit), usize::from(!bit));
+    let bounds = Bounds::<Zq, N>::new();
+
+    (0..MAX_PROOF_RETRIES).find_map(|_| {
+        // the simulated branch has uniform responses and a chosen challenge
+        let mut sim = BitProofBranch {
+            challenge: [0u8; 32],
+            z_r: mask_polynomial::<Zq, N>(rng, &bounds.max_z_s),
+            z_e2: mask_polynomial::<Zq, N>(rng, &bounds.max_z_s),
+            z_e3: mask_polynomial::<Zq, N>(rng, &bounds.max_z_s),
+        };
+        rng.fill_bytes(&mut sim.challenge);
+        let w_sim = bit_commitment(ek, c, !bit, &sim);
+
+        // w = (a * y_r + y_e2, t * y_r + y_e3)
+        let y_r = mask_polynomial::<Zq, N>(rng, &bounds.gamma_s);
+        let y_e2 = mask_polynomial::<Zq, N>(rng, &bounds.gamma_s);
+        let y_e3 = mask_polynomial::<Zq, N>(rng, &bounds.gamma_s);
+        let a_y_r = mul_polynomials::<Zq, N>(&ek.a, &y_r);
+        let t_y_r = mul_polynomials::<Zq, N>(&ek.t, &y_r);
+        let w_real = (
+            modulo_coefficients::<Zq, N>(a_y_r + y_e2.clone()),
+            modulo_coefficients::<Zq, N>(t_y_r + y_e3.clone()),
+        );
+
+        let mut commitments = [w_real.clone(), w_real];
+        commitments[simulated] = w_sim;
+        let mut challenge = bit_challenge_hash(ek, c, &commitments);
+        challenge
+            .iter_mut()
+            .zip(sim.challenge)
+            .for_each(|(x, y)| *x ^= y);
+        let ch = challenge_polynomial::<Zq, N>(&challenge);
+
+        // z = y + ch * secret
+        let respond = |y: Polynomial<Zq::I, N>, secret: &Polynomial<Zq::I, N>| {
+            modulo_coefficients::<Zq, N>(y + mul_polynomials::<Zq, N>(&ch, secret))
+        };
+        let branch = BitProofBranch {
+            challenge,
+            z_r: respond(y_r, &opening.r),
+            z_e2: respond(y_e2, &opening.e2),
+            z_e3: respond(y_e3, &opening.e3),
+        };
+        bounds.accepts_branch(&branch).then(|| {
+            let mut branches = [sim.clone(), sim];
+            branches[real] = branch;
+            BitProof { branches }
+        })
+    })
+}
+
+/// Verifies the proof produced by [prove_bit] that the ciphertext `c` under the encryption
+/// key `ek` encrypts a single bit.
+pub fn verify_bit<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    proof: &BitProof<Zq, N>,
+) -> bool
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let bounds = Bounds::<Zq, N>::new();
+    let [zero, one] = &proof.branches;
+    if !c.is_well_formed() || !bounds.accepts_branch(zero) || !bounds.accepts_branch(one) {
+        return false;
+    }
+    let commitments = [
+        bit_commitment(ek, c, false, zero),
+        bit_commitment(ek, c, true, one),
+    ];
+    let mut challenge = bit_challenge_hash(ek, c, &commitments);
+    challenge
+        .iter_mut()
+        .zip(zero.challenge)
+        .for_each(|(x, y)| *x ^= y);
+    challenge == one.challenge
+}
+
+/// The commitment `(w1, w2)` of a branch of [BitProof].
+type Commitment<I, const N: usize> = (Polynomial<I, N>, Polynomial<I, N>);
+
+/// Returns the message `[0]` or `[1]`.
+fn bit_message<Zq: IntField, const N: usize>(bit: bool) -> Message<Zq, N> {
+    Message {
+        data: vec![if bit { Zq::I::one() } else { Zq::I::zero() }],
+    }
+}
+
+/// Computes the commitment of the branch for `bit` from its challenge and responses, i.e.
+/// `(a * z_r + z_e2 - ch * u, t * z_r + z_e3 - ch * (v - [q/2] bit))`.
+fn bit_commitment<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    bit: bool,
+    branch: &BitProofBranch<Zq::I, N>,
+) -> Commitment<Zq::I, N>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let ch = challenge_polynomial::<Zq, N>(&branch.challenge);
+    let w1 = sub_polynomials::<Zq, N>(
+        mul_polynomials::<Zq, N>(&ek.a, &branch.z_r) + branch.z_e2.clone(),
+        mul_polynomials::<Zq, N>(&ch, &c.u),
+    );
+    let w2 = sub_polynomials::<Zq, N>(
+        mul_polynomials::<Zq, N>(&ek.t, &branch.z_r) + branch.z_e3.clone(),
+        mul_polynomials::<Zq, N>(&ch, &shifted_v(c, &bit_message(bit))),
+    );
+    (w1, w2)
+}
+
+/// Hashes the statement `(ek, c)` and the commitments of both branches into the XOR of
+/// their challenges.
+fn bit_challenge_hash<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    commitments: &[Commitment<Zq::I, N>; 2],
+) -> [u8; 32]
+where
+    Zq::I: ToPrimitive,
+{
+    let [(w1, w2), (w3, w4)] = commitments;
+    let input = pack_coefficients::<Zq, N>(&[&ek.a, &ek.t, &c.u, &c.v, w1, w2, w3, w4]);
+    let mut challenge = [0u8; 32];
+    XofRng::new(BIT_PROOF_DOMAIN, &input).fill_bytes(&mut challenge);
+    challenge
+}
+
+/// The bounds on the coefficients of the masks and the responses.
+struct Bounds<Zq: IntField, const N: usize> {
+    /// The bound on the noise `n` for which a proof can be produced.
+    noise: Zq::I,
+    /// The bound on the masks of `s` and `e`.
+    gamma_s: Zq::I,
+    /// The bound on the mask of `n`.
+    gamma_n: Zq::I,
+    /// The bound on the responses of `s` and `e`, i.e. `gamma_s - weight * B`.
+    max_z_s: Zq::I,
+    /// The bound on the response of `n`, i.e. `gamma_n - weight * noise`.
+    max_z_n: Zq::I,
+}
+
+impl<Zq: IntField, const N: usize> Bounds<Zq, N>
+where
+    Zq::I: FromPrimitive,
+{
+    fn new() -> Self {
+        let n = Zq::I::from_usize(N).unwrap();
+        let weight = Zq::I::from_usize(CHALLENGE_WEIGHT).unwrap();
+        let eight = Zq::I::from_u8(8).unwrap();
+
+        // the mask is N times larger than the shift by the challenge, so that the rejection
+        // succeeds with a constant probability
+        let noise = Zq::Q.clone() / (eight * n.clone() * weight.clone());
+        let beta_s = weight.clone() * Zq::B.clone();
+        let beta_n = weight * noise.clone();
+        let gamma_s = n.clone() * beta_s.clone();
+        let gamma_n = n * beta_n.clone();
+        Self {
+            noise,
+            max_z_s: gamma_s.clone() - beta_s,
+            max_z_n: gamma_n.clone() - beta_n,
+            gamma_s,
+            gamma_n,
+        }
+    }
+
+    fn accepts_secret(&self, proof: &DecryptionProof<Zq, N>) -> bool {
+        linf_norm::<Zq, N>(&proof.z_s) <= self.max_z_s
+            && linf_norm::<Zq, N>(&proof.z_e) <= self.max_z_s
+    }
+
+    fn accepts_noise(&self, proof: &DecryptionProof<Zq, N>) -> bool {
+        linf_norm::<Zq, N>(&proof.z_n) <= self.max_z_n
+    }
+
+    /// The randomness `(r, e2, e3)` has the same bound `B` as `s` and `e`.
+    fn accepts_branch(&self, branch: &BitProofBranch<Zq::I, N>) -> bool {
+        [&branch.z_r, &branch.z_e2, &branch.z_e3]
+            .into_iter()
+            .all(|z| linf_norm::<Zq, N>(z) <= self.max_z_s)
+    }
+}
+
+/// Proves knowledge of small `s`, `e` and `n` with `t = a * s + e` and `target = u * s + n`,
+/// where `hash` computes the challenge from the statement and the commitment `(w1, w2)`.
+fn prove_relation<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    s: &Polynomial<Zq::I, N>,
+    u: &Polynomial<Zq::I, N>,
+    target: &Polynomial<Zq::I, N>,
+    hash: impl Fn(&Polynomial<Zq::I, N>, &Polynomial<Zq::I, N>) -> [u8; 32],
+    rng: &mut impl Rng,
+) -> Option<DecryptionProof<Zq, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let bounds = Bounds::<Zq, N>::new();
+    // e = t - a * s
+    let e = sub_polynomials::<Zq, N>(ek.t.clone(), mul_polynomials::<Zq, N>(&ek.a, s));
+    // n = target - u * s
+    let n = sub_polynomials::<Zq, N>(target.clone(), mul_polynomials::<Zq, N>(u, s));
+    if linf_norm::<Zq, N>(&n) > bounds.noise {
+        return None;
+    }
+
+    (0..MAX_PROOF_RETRIES).find_map(|_| {
+        let y_s = mask_polynomial::<Zq, N>(rng, &bounds.gamma_s);
+        let y_e = mask_polynomial::<Zq, N>(rng, &bounds.gamma_s);
+        let y_n = mask_polynomial::<Zq, N>(rng, &bounds.gamma_n);
+
+        // w1 = a * y_s + y_e, w2 = u * y_s + y_n
+        let a_y_s = mul_polynomials::<Zq, N>(&ek.a, &y_s);
+        let w1 = modulo_coefficients::<Zq, N>(a_y_s + y_e.clone());
+        let u_y_s = mul_polynomials::<Zq, N>(u, &y_s);
+        let w2 = modulo_coefficients::<Zq, N>(u_y_s + y_n.clone());
+        let challenge = hash(&w1, &w2);
+        let ch = challenge_polynomial::<Zq, N>(&challenge);
+
+        // z = y + ch * secret
+        let respond = |y: Polynomial<Zq::I, N>, secret: &Polynomial<Zq::I, N>| {
+            modulo_coefficients::<Zq, N>(y + mul_polynomials::<Zq, N>(&ch, secret))
+        };
+        let proof = DecryptionProof {
+            challenge,
+            z_s: respond(y_s, s),
+            z_e: respond(y_e, &e),
+            z_n: respond(y_n, &n),
+        };
+        (bounds.accepts_secret(&proof) && bounds.accepts_noise(&proof)).then_some(proof)
+    })
+}
+
+/// Verifies the proof produced by [prove_relation] for the statement `t = a * s + e` and
+/// `target = u * s + n`.
+fn verify_relation<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    u: &Polynomial<Zq::I, N>,
+    target: &Polynomial<Zq::I, N>,
+    proof: &DecryptionProof<Zq, N>,
+    hash: impl Fn(&Polynomial<Zq::I, N>, &Polynomial<Zq::I, N>) -> [u8; 32],
+) -> bool
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let bounds = Bounds::<Zq, N>::new();
+    if !bounds.accepts_secret(proof) || !bounds.accepts_noise(proof) {
+        return false;
+    }
+    let ch = challenge_polynomial::<Zq, N>(&proof.challenge);
+
+    // w1 = a * z_s + z_e - ch * t, w2 = u * z_s + z_n - ch * target
+    let w1 = sub_polynomials::<Zq, N>(
+        mul_polynomials::<Zq, N>(&ek.a, &proof.z_s) + proof.z_e.clone(),
+        mul_polynomials::<Zq, N>(&ch, &ek.t),
+    );
+    let w2 = sub_polynomials::<Zq, N>(
+        mul_polynomials::<Zq, N>(u, &proof.z_s) + proof.z_n.clone(),
+        mul_polynomials::<Zq, N>(&ch, target),
+    );
+    hash(&w1, &w2) == proof.challenge
+}
+
+/// Samples a mask polynomial with coefficients uniform in `[-gamma, gamma]`.
+fn mask_polynomial<Zq: IntField, const N: usize>(
+    rng: &mut impl Rng,
+    gamma: &Zq::I,
+) -> Polynomial<Zq::I, N> {
+    rand_polynomial_within::<_, _, N>(rng, -gamma.clone(), gamma.clone())
+}
+
+/// Computes `v - [q/2] m`.
+fn shifted_v<Zq: IntField, const N: usize>(
+    c: &CipherText<Zq, N>,
+    m: &Message<Zq, N>,
+) -> Polynomial<Zq::I, N>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let scaled_m = scale_coefficients::<Zq, N>(Polynomial::new(m.data.clone()));
+    sub_polynomials::<Zq, N>(c.v.clone(), scaled_m)
+}
+
+/// Hashes the statement `(ek, c, m)` and the commitment `(w1, w2)` into the challenge.
+fn challenge_hash<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    m: &Message<Zq, N>,
+    w1: &Polynomial<Zq::I, N>,
+    w2: &Polynomial<Zq::I, N>,
+) -> [u8; 32]
+where
+    Zq::I: ToPrimitive,
+{
+    let mut input = pack_coefficients::<Zq, N>(&[&ek.a, &ek.t, &c.u, &c.v, w1, w2]);
+    input.extend(m.to_bytes());
+    let mut challenge = [0u8; 32];
+    XofRng::new(PROOF_DOMAIN, &input).fill_bytes(&mut challenge);
+    challenge
+}
+
+/// Hashes the statement `(ek, c, d)` of a partial decryption and the commitment `(w1, w2)`
+/// into the challenge.
+fn partial_challenge_hash<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    d: &Polynomial<Zq::I, N>,
+    w1: &Polynomial<Zq::I, N>,
+    w2: &Polynomial<Zq::I, N>,
+) -> [u8; 32]
+where
+    Zq::I: ToPrimitive,
+{
+    let input = pack_coefficients::<Zq, N>(&[&ek.a, &ek.t, &c.u, &c.v, d, w1, w2]);
+    let mut challenge = [0u8; 32];
+    XofRng::new(PARTIAL_DECRYPTION_DOMAIN, &input).fill_bytes(&mut challenge);
+    challenge
+}
+
+/// Expands the challenge into a polynomial with [CHALLENGE_WEIGHT] coefficients of `1` or
+/// `-1` at distinct positions.
+fn challenge_polynomial<Zq: IntField, const N: usize>(
+    challenge: &[u8; 32],
+) -> Polynomial<Zq::I, N> {
+    let rng = &mut XofRng::new(CHALLENGE_DOMAIN, challenge);
+    let mut coeffs = vec![Zq::I::zero(); N];
+    let mut count = 0;
+    while count < CHALLENGE_WEIGHT.min(N) {
+        let i = rng.random_range(0..N);
+        if coeffs[i].is_zero() {
+            coeffs[i] = if rng.random() {
+                Zq::I::one()
+            } else {
+                -Zq::I::one()
+            };
+            count += 1;
+        }
+    }
+    Polynomial::new(coeffs)
+}
+
+fn linf_norm<Zq: IntField, const N: usize>(p: &Polynomial<Zq::I, N>) -> Zq::I {
+    p.iter().map(|c| c.abs()).max().unwrap_or_else(Zq::I::zero)
+}
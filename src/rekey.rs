@@ -0,0 +1,161 @@
+//! Defines the re-encryption key for migrating ciphertexts to a new key pair.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+use num::{Integer, One, Signed, Zero};
+use poly_ring_xnp1::Polynomial;
+use rand::Rng;
+
+use crate::{
+    ciphertext::CipherText,
+    intfield::IntField,
+    polynomial::{modulo_coefficients, mul_polynomials, small_polynomial, to_fixed_coeffs_vec},
+    DecryptKey, EncryptKey,
+};
+
+/// The re-encryption (key switching) key from an old decryption key to a new encryption key.
+///
+/// It consists of the encryptions of `-2^i * s` under the new key, for each bit `i` of `Q`,
+/// where `s` is the old secret. A ciphertext `(u, v)` under the old key is migrated by
+/// decomposing `u` into bits `u_i`, and computing `(sum u_i * rk_i.u, v + sum u_i * rk_i.v)`.
+/// The old secret is not revealed to the holder of the re-encryption key, so the migration
+/// can be delegated to the storage of the ciphertexts.
+///
+/// The noise grows with `N^2 * log2(Q) * B^2`, so `Q` must be much larger than the
+/// condition for fresh ciphertexts (e.g. `Q = 8383489` for `N = 512`). The standard field
+/// `Q = 3329` does not leave enough room and the re-encrypted ciphertexts fail to decrypt.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReKey<Zq: IntField, const N: usize> {
+    pub(crate) keys: Vec<CipherText<Zq, N>>,
+}
+
+impl<Zq: IntField, const N: usize> ReKey<Zq, N> {
+    /// Creates the re-encryption key from the old decryption key `dk` to the new
+    /// encryption key `ek`.
+    pub fn new(rng: &mut impl Rng, dk: &DecryptKey<Zq, N>, ek: &EncryptKey<Zq, N>) -> Self
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let two = Zq::I::one() + Zq::I::one();
+        let mut power = Zq::I::one();
+        let keys = (0..bit_length::<Zq>())
+            .map(|_| {
+                let r = small_polynomial::<Zq, N>(rng);
+                let e2 = small_polynomial::<Zq, N>(rng);
+                let e3 = small_polynomial::<Zq, N>(rng);
+
+                // -2^i * s
+                let mut power_s = dk.s.clone();
+                power_s.coeffs_mut(|c| *c = Zq::modulo(&(-(power.clone() * c.clone()))));
+                power = Zq::modulo(&(power.clone() * two.clone()));
+
+                // u = a * r + e2, v = t * r + e3 - 2^i * s
+                let u = {
+                    let a_r = mul_polynomials::<Zq, N>(&ek.a, &r);
+                    modulo_coefficients::<Zq, N>(a_r + e2)
+                };
+                let v = {
+                    let t_r = mul_polynomials::<Zq, N>(&ek.t, &r);
+                    let t_r_e3 = modulo_coefficients::<Zq, N>(t_r + e3);
+                    modulo_coefficients::<Zq, N>(t_r_e3 + power_s)
+                };
+                CipherText { u, v }
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Re-encrypts the ciphertext `c` under the old key into a ciphertext of the same
+    /// message under the new key.
+    pub fn reencrypt(&self, c: &CipherText<Zq, N>) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let mut u = Polynomial::<Zq::I, N>::zero();
+        let mut v = c.v.clone();
+        for (u_i, rk_i) in decompose::<Zq, N>(&c.u).into_iter().zip(self.keys.iter()) {
+            u = modulo_coefficients::<Zq, N>(u + u_i.clone() * rk_i.u.clone());
+            v = modulo_coefficients::<Zq, N>(v + u_i * rk_i.v.clone());
+        }
+        CipherText { u, v }
+    }
+}
+
+/// Re-encrypts all the ciphertexts `cs` with the re-encryption key `rekey`, e.g. to migrate
+/// the stored ciphertexts after a key rotation. See [ReKey::reencrypt].
+///
+/// With the `rayon` feature, the ciphertexts are re-encrypted in parallel on the rayon
+/// thread pool, which additionally requires `Zq::I: Send + Sync`. The result is in the same
+/// order as `cs` in both cases.
+#[cfg(not(feature = "rayon"))]
+pub fn reencrypt_batch<Zq: IntField, const N: usize>(
+    rekey: &ReKey<Zq, N>,
+    cs: Vec<CipherText<Zq, N>>,
+) -> Vec<CipherText<Zq, N>>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    cs.iter().map(|c| rekey.reencrypt(c)).collect()
+}
+
+/// Re-encrypts all the ciphertexts `cs` with the re-encryption key `rekey`, e.g. to migrate
+/// the stored ciphertexts after a key rotation. See [ReKey::reencrypt].
+///
+/// With the `rayon` feature, the ciphertexts are re-encrypted in parallel on the rayon
+/// thread pool, which additionally requires `Zq::I: Send + Sync`. The result is in the same
+/// order as `cs` in both cases.
+#[cfg(feature = "rayon")]
+pub fn reencrypt_batch<Zq: IntField, const N: usize>(
+    rekey: &ReKey<Zq, N>,
+    cs: Vec<CipherText<Zq, N>>,
+) -> Vec<CipherText<Zq, N>>
+where
+    Zq::I: Send + Sync,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    use rayon::prelude::*;
+
+    cs.par_iter().map(|c| rekey.reencrypt(c)).collect()
+}
+
+/// Returns the number of bits of `Q - 1`, i.e. the number of bits of a coefficient in `[0, Q)`.
+fn bit_length<Zq: IntField>() -> usize {
+    let two = Zq::I::one() + Zq::I::one();
+    let mut x = Zq::Q - Zq::I::one();
+    let mut bits = 0;
+    while !x.is_zero() {
+        x = x / two.clone();
+        bits += 1;
+    }
+    bits
+}
+
+/// Decomposes the polynomial, with coefficients taken in `[0, Q)`, into binary polynomials
+/// `p_i` such that `p = sum 2^i * p_i`.
+fn decompose<Zq: IntField, const N: usize>(p: &Polynomial<Zq::I, N>) -> Vec<Polynomial<Zq::I, N>> {
+    let two = Zq::I::one() + Zq::I::one();
+    let mut coeffs = to_fixed_coeffs_vec::<Zq, N>(p)
+        .into_iter()
+        .map(|c| if c.is_negative() { c + Zq::Q } else { c })
+        .collect::<Vec<_>>();
+
+    (0..bit_length::<Zq>())
+        .map(|_| {
+            let bits = coeffs
+                .iter_mut()
+                .map(|c| {
+                    let (q, r) = c.div_rem(&two);
+                    *c = q;
+                    r
+                })
+                .collect();
+            Polynomial::new(bits)
+        })
+        .collect()
+}
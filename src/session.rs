@@ -0,0 +1,70 @@
+//! Defines the [Session] for encrypting many messages with reproducible randomness.
+
+use core::ops::{Add, Mul, Sub};
+
+use crate::{intfield::IntField, xof::XofRng, CipherText, EncryptKey, Message};
+
+/// The domain separation tag for deriving the randomness of each message in a [Session].
+const SESSION_DOMAIN: &[u8] = b"rlwe-encryption/session";
+
+/// A session encrypting a sequence of messages with an encryption key, in which the
+/// randomness of the `i`-th message is derived from `base_seed || i`.
+///
+/// The randomness of each message is distinct, and the whole transcript of the session
+/// can be reproduced from the base seed without storing the randomness of each message.
+/// The base seed must be kept secret and never reused for another session, because anyone
+/// knowing it can recompute the randomness and thus decrypt the messages.
+///
+/// ```
+/// use rlwe_encryption::{Message, Session};
+///
+/// let rng = &mut rand::rng();
+/// let (ek, dk) = rlwe_encryption::standard(rng);
+///
+/// let mut session = Session::new(ek, [1u8; 32]);
+/// let c = session.encrypt_next(Message::new(vec![1, 0, 1]));
+/// assert_eq!(dk.decrypt(c)[..3], [1, 0, 1]);
+/// assert_eq!(session.counter(), 1);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Session<Zq: IntField, const N: usize> {
+    ek: EncryptKey<Zq, N>,
+    base_seed: [u8; 32],
+    counter: u64,
+}
+
+impl<Zq: IntField, const N: usize> Session<Zq, N> {
+    /// Creates a session with the encryption key `ek` and the secret `base_seed`, starting
+    /// from the counter 0.
+    pub fn new(ek: EncryptKey<Zq, N>, base_seed: [u8; 32]) -> Self {
+        Self {
+            ek,
+            base_seed,
+            counter: 0,
+        }
+    }
+
+    /// Returns the number of messages encrypted so far, i.e. the counter of the next message.
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Returns the encryption key of the session.
+    pub fn encrypt_key(&self) -> &EncryptKey<Zq, N> {
+        &self.ek
+    }
+
+    /// Encrypts the message `m` with the randomness derived from the base seed and the
+    /// current counter, and increments the counter.
+    pub fn encrypt_next(&mut self, m: Message<Zq, N>) -> CipherText<Zq, N>
+    where
+        for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+    {
+        let mut input = [0u8; 40];
+        input[..32].copy_from_slice(&self.base_seed);
+        input[32..].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+
+        self.ek.encrypt(&mut XofRng::new(SESSION_DOMAIN, &input), m)
+    }
+}
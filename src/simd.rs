@@ -0,0 +1,53 @@
+//! Defines the vectorized modulo operation on `i32` coefficients with [wide].
+
+use wide::{f32x8, i32x8, CmpGt, CmpLt};
+
+/// The number of coefficients reduced at once by [modulo_i32_slice].
+const LANES: usize = 8;
+
+/// Reduces each integer in `xs` modulo `q` into the symmetric representation, i.e. the same
+/// result as the example implementation of [IntField::modulo](crate::IntField::modulo)
+/// (`x.rem_euclid(q)` minus `q` if it is larger than `q/2`), but [LANES] integers at a time.
+///
+/// Fields with `I = i32` can override [IntField::modulo_slice](crate::IntField::modulo_slice)
+/// with it and set [IntField::MODULO_SLICE](crate::IntField::MODULO_SLICE), as
+/// [StandardZq](crate::StandardZq) does with the `simd` feature.
+///
+/// The quotient is estimated as `round(x * (1/q))` in `f32`. The first estimate is only
+/// accurate up to the 24 bits of the `f32` mantissa, which leaves a remainder below `q/2 + 2^8`
+/// in magnitude. The second estimate on the remainder is off by at most one, and the final
+/// remainder is corrected with masks like the scalar Barrett reduction.
+///
+/// ## Safety
+/// `q` must be positive and less than `2^23`.
+pub fn modulo_i32_slice(xs: &mut [i32], q: i32) {
+    let q_inv = f32x8::splat(1.0 / q as f32);
+    let q_i32 = i32x8::splat(q);
+    let max = i32x8::splat(q / 2);
+    let min = i32x8::splat(q / 2 - q + 1);
+    let exact_max = i32x8::splat(1 << 24);
+    let exact_min = i32x8::splat(-(1 << 24));
+    // the remainder of x - round(x / q) * q, exact in the wrapping arithmetic as long as the
+    // quotient estimate is close enough
+    let remainder = |x: i32x8| x - (x.round_float() * q_inv).fast_round_int() * q_i32;
+
+    let mut chunks = xs.chunks_exact_mut(LANES);
+    for chunk in &mut chunks {
+        let x = i32x8::new(chunk.try_into().unwrap());
+        // the first estimate is already off by at most one if x is exact in f32, e.g. after
+        // multiplying a uniform and a small polynomial
+        let is_large = x.cmp_lt(exact_min) | x.cmp_gt(exact_max);
+        let r = if is_large.none() {
+            remainder(x)
+        } else {
+            remainder(remainder(x))
+        }; // in [-3q/2, 3q/2]
+        let r = r.cmp_gt(max).blend(r - q_i32, r); // in [-3q/2, q/2]
+        let r = r.cmp_lt(min).blend(r + q_i32, r); // in [q/2 - q + 1, q/2]
+        chunk.copy_from_slice(r.as_array_ref());
+    }
+    chunks.into_remainder().iter_mut().for_each(|c| {
+        let a = c.rem_euclid(q);
+        *c = if a > q / 2 { a - q } else { a };
+    });
+}
@@ -0,0 +1,160 @@
+//! Defines the threshold decryption, in which the secret is shared additively among several
+//! parties, and the ciphertext is decrypted by combining their partial decryptions.
+//!
+//! Each party generates a share key `(a, t_i = a * s_i + e_i)` with a common `a`, e.g. with
+//! [key_gen_from_crs](crate::key_gen_from_crs), and publishes it as the commitment to its
+//! secret share `s_i`. Unlike an exact `a * s_i`, the commitment does not reveal the share.
+//! The joint encryption key is `(a, sum(t_i))` (see [joint_encrypt_key]), whose secret is
+//! `sum(s_i)`, so every party must contribute to the decryption.
+
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+use num::{FromPrimitive, ToPrimitive};
+use poly_ring_xnp1::Polynomial;
+use rand::Rng;
+
+use crate::{
+    polynomial::{
+        modulo_coefficients, mul_polynomials, round_coefficients, small_polynomial,
+        sub_polynomials, to_fixed_coeffs_vec,
+    },
+    proof::{prove_partial_decryption, verify_partial_decryption},
+    CipherText, DecryptKey, DecryptionProof, EncryptKey, Error, IntField,
+};
+
+/// A partial decryption `d = u * s_i + n` of a ciphertext with the share `s_i`, produced by
+/// [partial_decrypt] and combined by [combine_partials_verified]. The small noise `n` keeps
+/// the share from being solved from `d` and `u`.
+///
+/// It carries a proof that `d` is consistent with the commitment to the share, so that a
+/// bogus partial decryption is detected instead of corrupting the result.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialDecryption<Zq: IntField, const N: usize> {
+    /// The partial decryption `u * s_i + n`.
+    pub d: Polynomial<Zq::I, N>,
+    pub(crate) proof: DecryptionProof<Zq, N>,
+}
+
+/// Computes the joint encryption key `(a, sum(t_i))` from the share keys (the commitments)
+/// of all parties. Returns [Error::InvalidLength] if there is no share key, or
+/// [Error::InvalidParams] if they do not share the same common reference string `a`.
+///
+/// The noise of the joint key grows with the number of parties, so the parameters must
+/// leave a margin for the sum of the noises.
+pub fn joint_encrypt_key<Zq: IntField, const N: usize>(
+    commitments: &[EncryptKey<Zq, N>],
+) -> Result<EncryptKey<Zq, N>, Error>
+where
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    let (first, rest) = commitments.split_first().ok_or(Error::InvalidLength)?;
+    if !rest.iter().all(|ek| ek.same_crs(first)) {
+        return Err(Error::InvalidParams);
+    }
+    let t = rest.iter().fold(first.t.clone(), |t, ek| {
+        modulo_coefficients::<Zq, N>(t + ek.t.clone())
+    });
+    Ok(EncryptKey {
+        a: first.a.clone(),
+        t,
+    })
+}
+
+/// Computes the partial decryption of the ciphertext `c` (encrypted under the joint key)
+/// with the share `dk`, together with a proof that it is consistent with the share key
+/// `ek` of the party.
+///
+/// Returns `None` if no proof can be produced, under the same conditions as
+/// [prove_decryption](crate::prove_decryption), e.g. with a small modulus such as
+/// [StandardZq](crate::StandardZq) or if `dk` is not the secret of `ek`.
+pub fn partial_decrypt<Zq: IntField, const N: usize>(
+    ek: &EncryptKey<Zq, N>,
+    dk: &DecryptKey<Zq, N>,
+    c: &CipherText<Zq, N>,
+    rng: &mut impl Rng,
+) -> Option<PartialDecryption<Zq, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    if !c.is_well_formed() {
+        return None;
+    }
+    // d = u * s_i + n
+    let u_s = mul_polynomials::<Zq, N>(&c.u, &dk.s);
+    let d = modulo_coefficients::<Zq, N>(u_s + small_polynomial::<Zq, N>(rng));
+    let proof = prove_partial_decryption(ek, dk, c, &d, rng)?;
+    Some(PartialDecryption { d, proof })
+}
+
+/// Verifies the partial decryption of each party against its commitment, i.e. the share
+/// key at the same index in `commitments`, and combines them into the decryption of the
+/// ciphertext `c`, i.e. `v - sum(d_i)` rounded to {0, 1}. The decrypted message has the
+/// length `N`, as returned by [DecryptKey::decrypt].
+///
+/// Returns [Error::InvalidPartialDecryption] with the index of the first partial
+/// decryption that does not verify, so that the party can be excluded or asked again.
+/// Returns [Error::InvalidLength] if the numbers of commitments and partial decryptions
+/// differ or are zero, [Error::InvalidParams] if the commitments do not share the same
+/// `a`, and [Error::InvalidCipherText] if the ciphertext is malformed.
+///
+/// ## Soundness
+/// Like [verify_decryption](crate::verify_decryption), the proof only bounds the noise of
+/// a partial decryption by about `Q/4`, instead of the honest `B`. It detects a partial
+/// decryption that is not computed from the committed share, while a dishonest party that
+/// adds a noise close to the bound can still shift the combined coefficients.
+///
+/// ```
+/// use rlwe_encryption::{
+///     combine_partials_verified, joint_encrypt_key, key_gen_from_crs, partial_decrypt,
+///     standard_i64, Message,
+/// };
+///
+/// let rng = &mut rand::rng();
+/// let (ek1, dk1) = standard_i64(rng);
+/// let (ek2, dk2) = key_gen_from_crs(&ek1, rng);
+/// let commitments = [ek1, ek2];
+/// let ek = joint_encrypt_key(&commitments).unwrap();
+///
+/// let c = ek.encrypt(rng, Message::new(vec![1, 0, 1]));
+/// let partials = [
+///     partial_decrypt(&commitments[0], &dk1, &c, rng).unwrap(),
+///     partial_decrypt(&commitments[1], &dk2, &c, rng).unwrap(),
+/// ];
+/// let m = combine_partials_verified(&commitments, &c, &partials).unwrap();
+/// assert_eq!(m[..3], [1, 0, 1]);
+/// ```
+pub fn combine_partials_verified<Zq: IntField, const N: usize>(
+    commitments: &[EncryptKey<Zq, N>],
+    c: &CipherText<Zq, N>,
+    partials: &[PartialDecryption<Zq, N>],
+) -> Result<Vec<Zq::I>, Error>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I: Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I>,
+{
+    if commitments.is_empty() || commitments.len() != partials.len() {
+        return Err(Error::InvalidLength);
+    }
+    if !commitments.iter().all(|ek| ek.same_crs(&commitments[0])) {
+        return Err(Error::InvalidParams);
+    }
+    if !c.is_well_formed() {
+        return Err(Error::InvalidCipherText);
+    }
+    if let Some(index) = commitments
+        .iter()
+        .zip(partials)
+        .position(|(ek, p)| !verify_partial_decryption(ek, c, &p.d, &p.proof))
+    {
+        return Err(Error::InvalidPartialDecryption { index });
+    }
+
+    // m = v - sum(d_i)
+    let m = partials
+        .iter()
+        .fold(c.v.clone(), |m, p| sub_polynomials::<Zq, N>(m, p.d.clone()));
+    let mb = round_coefficients::<Zq, N>(m);
+    Ok(to_fixed_coeffs_vec::<Zq, N>(&mb))
+}
@@ -0,0 +1,214 @@
+//! Generates portable test vectors for validating other implementations of the scheme.
+
+use alloc::{format, string::String, vec::Vec};
+use core::ops::{Add, Mul, Neg, Sub};
+use num::{FromPrimitive, ToPrimitive};
+use poly_ring_xnp1::Polynomial;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    encrypt::Opening, intfield::IntField, key_gen, polynomial::to_fixed_coeffs_vec, xof::XofRng,
+    DecryptKey, EncryptKey, Error, Message,
+};
+
+/// The domain separation tag for deriving the seeds of the test vectors.
+const TEST_VECTORS_DOMAIN: &[u8] = b"rlwe-encryption/test-vectors";
+
+/// A set of test vectors produced by [export_test_vectors].
+///
+/// All the polynomials are given as their `N` coefficients in the standard representation
+/// `[0, Q)`, so that they can be consumed by implementations in other languages.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TestVectors {
+    /// The length of the polynomials.
+    pub n: usize,
+    /// The modulus.
+    pub q: u64,
+    /// The boundary of the coefficients of the small polynomials.
+    pub b: u64,
+    /// The test vectors.
+    pub vectors: Vec<TestVector>,
+}
+
+/// A single test vector: a key pair, a message, the encryption randomness, the resulting
+/// ciphertext and its decryption.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TestVector {
+    /// The hex-encoded 32-byte seed from which the keys were generated.
+    pub key_seed: String,
+    /// The hex-encoded 32-byte seed from which the message and randomness were generated.
+    pub encrypt_seed: String,
+    /// The public polynomial `a` of the encryption key.
+    pub a: Vec<u64>,
+    /// The polynomial `t = a * s + e` of the encryption key.
+    pub t: Vec<u64>,
+    /// The secret polynomial `s` of the decryption key.
+    pub s: Vec<u64>,
+    /// The binary message.
+    pub message: Vec<u64>,
+    /// The randomness `r` of the encryption.
+    pub r: Vec<u64>,
+    /// The randomness `e2` of the encryption.
+    pub e2: Vec<u64>,
+    /// The randomness `e3` of the encryption.
+    pub e3: Vec<u64>,
+    /// The polynomial `u = a * r + e2` of the ciphertext.
+    pub u: Vec<u64>,
+    /// The polynomial `v = t * r + e3 + [q/2] m` of the ciphertext.
+    pub v: Vec<u64>,
+    /// The decryption of the ciphertext.
+    pub decryption: Vec<u64>,
+}
+
+impl TestVectors {
+    /// Deserializes the test vectors from the JSON string produced by [export_test_vectors].
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|_| Error::InvalidJson)
+    }
+
+    /// Recomputes the ciphertexts and decryptions from the keys, messages and randomness
+    /// with this implementation, and returns whether they match the test vectors.
+    pub fn verify<Zq: IntField, const N: usize>(&self) -> bool
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let params_match =
+            Zq::Q.to_u64() == Some(self.q) && Zq::B.to_u64() == Some(self.b) && self.n == N;
+        params_match && self.vectors.iter().all(|tv| tv.verify::<Zq, N>().is_some())
+    }
+}
+
+impl TestVector {
+    /// Returns `Some(())` if the test vector is consistent with this implementation.
+    fn verify<Zq: IntField, const N: usize>(&self) -> Option<()>
+    where
+        Zq::I: ToPrimitive + FromPrimitive,
+        for<'a> &'a Zq::I:
+            Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+    {
+        let ek = EncryptKey::<Zq, N> {
+            a: from_standard::<Zq, N>(&self.a)?,
+            t: from_standard::<Zq, N>(&self.t)?,
+        };
+        let dk = DecryptKey::<Zq, N> {
+            s: from_standard::<Zq, N>(&self.s)?,
+        };
+        let opening = Opening {
+            r: from_standard::<Zq, N>(&self.r)?,
+            e2: from_standard::<Zq, N>(&self.e2)?,
+            e3: from_standard::<Zq, N>(&self.e3)?,
+        };
+        let m = Message::try_new(
+            self.message
+                .iter()
+                .map(|&mi| Zq::I::from_u64(mi))
+                .collect::<Option<_>>()?,
+        )
+        .ok()?;
+
+        let c = ek.encrypt_with(&opening, m);
+        let ciphertext_match =
+            to_standard::<Zq, N>(&c.u) == self.u && to_standard::<Zq, N>(&c.v) == self.v;
+        let decryption = to_standard::<Zq, N>(&Polynomial::new(dk.decrypt(c)));
+        (ciphertext_match && decryption == self.decryption).then_some(())
+    }
+}
+
+/// Generates `count` test vectors deterministically from the `seed`, and returns them as
+/// a JSON string (see [TestVectors]).
+///
+/// The keys are generated as in [key_gen_from_seed](crate::key_gen_from_seed), and the
+/// messages and randomness are sampled from a seeded RNG, with the seeds derived from
+/// `seed` by SHAKE128. Since the seeded RNG of [rand] is not specified across versions,
+/// other implementations should start from the keys and randomness rather than the seeds.
+///
+/// ```
+/// use rlwe_encryption::{export_test_vectors, StandardZq, TestVectors};
+///
+/// let json = export_test_vectors::<StandardZq, 256>([7u8; 32], 2);
+/// let vectors = TestVectors::from_json(&json).unwrap();
+/// assert!(vectors.verify::<StandardZq, 256>());
+/// ```
+pub fn export_test_vectors<Zq: IntField, const N: usize>(seed: [u8; 32], count: usize) -> String
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+    for<'a> &'a Zq::I:
+        Add<Output = Zq::I> + Mul<Output = Zq::I> + Sub<Output = Zq::I> + Neg<Output = Zq::I>,
+{
+    let mut seeds = XofRng::new(TEST_VECTORS_DOMAIN, &seed);
+    let vectors = (0..count)
+        .map(|_| {
+            let mut key_seed = [0u8; 32];
+            let mut encrypt_seed = [0u8; 32];
+            seeds.fill_bytes(&mut key_seed);
+            seeds.fill_bytes(&mut encrypt_seed);
+
+            let (ek, dk) = key_gen::<Zq, N>(&mut StdRng::from_seed(key_seed));
+            let rng = &mut StdRng::from_seed(encrypt_seed);
+            let m = Message::<Zq, N>::random(rng, N);
+            let message = to_standard::<Zq, N>(&Polynomial::new(m.data.clone()));
+            let (c, opening) = ek.encrypt_with_opening(rng, m);
+            let (u, v) = (to_standard::<Zq, N>(&c.u), to_standard::<Zq, N>(&c.v));
+            let decryption = to_standard::<Zq, N>(&Polynomial::new(dk.decrypt(c)));
+
+            TestVector {
+                key_seed: to_hex(&key_seed),
+                encrypt_seed: to_hex(&encrypt_seed),
+                a: to_standard::<Zq, N>(&ek.a),
+                t: to_standard::<Zq, N>(&ek.t),
+                s: to_standard::<Zq, N>(&dk.s),
+                message,
+                r: to_standard::<Zq, N>(&opening.r),
+                e2: to_standard::<Zq, N>(&opening.e2),
+                e3: to_standard::<Zq, N>(&opening.e3),
+                u,
+                v,
+                decryption,
+            }
+        })
+        .collect();
+
+    let vectors = TestVectors {
+        n: N,
+        q: Zq::Q.to_u64().unwrap(),
+        b: Zq::B.to_u64().unwrap(),
+        vectors,
+    };
+    // serialization cannot fail as the struct contains only integers and strings
+    serde_json::to_string(&vectors).unwrap()
+}
+
+/// Returns the `N` coefficients of the polynomial in the range `[0, Q)`.
+fn to_standard<Zq: IntField, const N: usize>(p: &Polynomial<Zq::I, N>) -> Vec<u64>
+where
+    Zq::I: ToPrimitive,
+{
+    let q = Zq::Q.to_i64().unwrap();
+    to_fixed_coeffs_vec::<Zq, N>(p)
+        .iter()
+        .map(|c| c.to_i64().unwrap().rem_euclid(q) as u64)
+        .collect()
+}
+
+/// Returns the polynomial with the coefficients in the range `[0, Q)`, or `None` if the
+/// number of coefficients is not `N` or a coefficient is out of range.
+fn from_standard<Zq: IntField, const N: usize>(coeffs: &[u64]) -> Option<Polynomial<Zq::I, N>>
+where
+    Zq::I: ToPrimitive + FromPrimitive,
+{
+    if coeffs.len() != N {
+        return None;
+    }
+    let q = Zq::Q.to_u64()?;
+    let coeffs = coeffs
+        .iter()
+        .map(|&c| (c < q).then(|| Zq::I::from_u64(c).map(|c| Zq::modulo(&c)))?)
+        .collect::<Option<Vec<_>>>()?;
+    Some(Polynomial::new(coeffs))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
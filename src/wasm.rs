@@ -0,0 +1,73 @@
+//! Defines thin wrappers with [wasm_bindgen] to call the library from JavaScript.
+//!
+//! They use the [standard] parameters, i.e. [StandardZq] with `N = 256`, and exchange the
+//! keys and the ciphertexts in the compact format (see [EncryptKey::to_compact]).
+//!
+//! For the `wasm32-unknown-unknown` target, the randomness comes from the `crypto` API of
+//! the JavaScript runtime via the `wasm_js` backend of `getrandom`, which is selected by the
+//! `getrandom_backend` cfg in `.cargo/config.toml`.
+
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+use crate::{key_gen_from_seed, standard, CipherText, DecryptKey, EncryptKey, Message, StandardZq};
+
+/// The keys generated by [wasm_standard_keygen] in the compact format.
+#[wasm_bindgen]
+pub struct WasmKeyPair {
+    ek: Vec<u8>,
+    dk: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WasmKeyPair {
+    /// Returns the encryption key in the compact format.
+    #[wasm_bindgen(getter)]
+    pub fn ek(&self) -> Vec<u8> {
+        self.ek.clone()
+    }
+
+    /// Returns the decryption key in the compact format.
+    #[wasm_bindgen(getter)]
+    pub fn dk(&self) -> Vec<u8> {
+        self.dk.clone()
+    }
+}
+
+/// Generates the keys with the [standard] parameters. The keys are derived from the 32-byte
+/// `seed` (see [key_gen_from_seed]), or from the randomness of the runtime if the seed is
+/// empty.
+#[wasm_bindgen]
+pub fn wasm_standard_keygen(seed: &[u8]) -> Result<WasmKeyPair, JsError> {
+    let (ek, dk) = if seed.is_empty() {
+        standard(&mut rand::rng())
+    } else {
+        let seed = seed
+            .try_into()
+            .map_err(|_| JsError::new("seed must be empty or 32 bytes"))?;
+        key_gen_from_seed::<StandardZq, 256>(seed)?
+    };
+    Ok(WasmKeyPair {
+        ek: ek.to_compact(),
+        dk: dk.to_compact(),
+    })
+}
+
+/// Encrypts the message with the encryption key in the compact format. Each byte of
+/// `message_bits` is a bit of the message, i.e. `0` or `1`, and there are at most 256 of
+/// them. Returns the ciphertext in the compact format.
+#[wasm_bindgen]
+pub fn wasm_encrypt(ek_bytes: &[u8], message_bits: &[u8]) -> Result<Vec<u8>, JsError> {
+    let ek = EncryptKey::<StandardZq, 256>::from_compact(ek_bytes)?;
+    let m = Message::try_new(message_bits.iter().map(|&b| b as i32).collect())?;
+    Ok(ek.encrypt(&mut rand::rng(), m).to_compact())
+}
+
+/// Decrypts the ciphertext in the compact format with the decryption key in the compact
+/// format. Returns the 256 bits of the message, one per byte, padded with zeros.
+#[wasm_bindgen]
+pub fn wasm_decrypt(dk_bytes: &[u8], ct_bytes: &[u8]) -> Result<Vec<u8>, JsError> {
+    let dk = DecryptKey::<StandardZq, 256>::from_compact(dk_bytes)?;
+    let c = CipherText::from_compact(ct_bytes)?;
+    Ok(dk.decrypt(c).into_iter().map(|b| b as u8).collect())
+}
@@ -0,0 +1,61 @@
+//! Defines a deterministic RNG backed by the SHAKE128 extendable-output function.
+
+use rand::RngCore;
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake128, Shake128Reader,
+};
+
+/// A deterministic RNG whose output stream is `SHAKE128(len(domain) || domain || input)`,
+/// where `len(domain)` is the length of the domain in bytes as a little-endian `u64`.
+///
+/// Unlike the RNGs in [rand], the output stream is fully specified by the hash
+/// function, so it is suitable for deriving public values that other parties
+/// (or implementations) need to recompute.
+pub(crate) struct XofRng(Shake128Reader);
+
+impl XofRng {
+    /// Creates the RNG by absorbing the length-prefixed domain separation tag followed by
+    /// the input. The prefix keeps the domains apart even if one is a prefix of another, e.g.
+    /// the domains of the decryption proof and of its challenge.
+    pub(crate) fn new(domain: &[u8], input: &[u8]) -> Self {
+        let mut hasher = Shake128::default();
+        hasher.update(&(domain.len() as u64).to_le_bytes());
+        hasher.update(domain);
+        hasher.update(input);
+        Self(hasher.finalize_xof())
+    }
+}
+
+impl RngCore for XofRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.0.read(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.0.read(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.read(dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_domain_separation() {
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        XofRng::new(b"domain", b"-challenge").fill_bytes(&mut a);
+        XofRng::new(b"domain-challenge", b"").fill_bytes(&mut b);
+        assert_ne!(a, b);
+    }
+}
@@ -1,8 +1,15 @@
-use rand::rng;
-use rlwe_encryption::{key_gen, standard, IntField, Message};
+use rand::{rng, RngCore};
+use rlwe_encryption::{
+    check_params, key_gen, key_gen_from_crs, key_gen_from_seed, key_gen_with_label,
+    recommend_params, standard, DecryptKey, EncryptKey, Error, IntField, Message, Reduction,
+    StandardZq,
+};
 
 macro_rules! define_zq_i64 {
     ($name:ident, $q:expr) => {
+        define_zq_i64!($name, $q, Reduction::Modulo);
+    };
+    ($name:ident, $q:expr, $reduction:expr) => {
         #[derive(Clone, Debug)]
         struct $name;
 
@@ -10,6 +17,7 @@ macro_rules! define_zq_i64 {
             type I = i64;
             const Q: i64 = $q;
             const B: i64 = 1;
+            const REDUCTION: Reduction = $reduction;
 
             fn modulo(x: &Self::I) -> Self::I {
                 let a = x.rem_euclid(Self::Q);
@@ -19,6 +27,14 @@ macro_rules! define_zq_i64 {
                     a
                 }
             }
+
+            fn to_word(x: &Self::I) -> Option<i64> {
+                Some(*x)
+            }
+
+            fn from_word(x: i64) -> Option<Self::I> {
+                Some(x)
+            }
         }
     };
 }
@@ -69,6 +85,540 @@ fn test_key_gen_q16760833_n1024() {
     }
 }
 
+/// Test that the opening returned by the encryption verifies against its own message only.
+#[test]
+fn test_encrypt_with_opening() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let message = Message::new(vec![1]);
+    let (c, opening) = ek.encrypt_with_opening(rng, message.clone());
+    assert!(ek.verify_opening(&c, message.clone(), &opening));
+    assert!(!ek.verify_opening(&c, Message::new(vec![0]), &opening));
+    assert_eq!(dk.decrypt_exact(c, 1), message.data());
+}
+
+/// Test that in-place scalar multiplication matches the allocating version, and that the
+/// result decrypts to `k * m mod 2`.
+#[test]
+fn test_mul_scalar() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+
+    for k in [0, 1, 3] {
+        let mut c_assign = c.clone();
+        c_assign.mul_scalar_assign(&k);
+        let c_mul = c.mul_scalar(&k);
+        assert_eq!(c_assign, c_mul);
+
+        let expected = message
+            .clone()
+            .data()
+            .iter()
+            .map(|m| k * m % 2)
+            .collect::<Vec<_>>();
+        assert_eq!(dk.decrypt(c_mul), expected);
+    }
+}
+
+/// Test that keys generated from the same CRS are recognized, while independent keys are not.
+#[test]
+fn test_same_crs() {
+    let rng = &mut rng();
+    let (ek1, _) = standard(rng);
+    let (ek2, dk2) = key_gen_from_crs(&ek1, rng);
+    let (ek3, _) = standard(rng);
+
+    assert!(ek1.same_crs(&ek2));
+    assert_ne!(ek1, ek2);
+    assert!(!ek1.same_crs(&ek3));
+
+    let message = Message::random(rng, 256);
+    let c = ek2.encrypt(rng, message.clone());
+    assert_eq!(message.data(), dk2.decrypt(c));
+}
+
+/// Test that the same label yields the same `a` with different secrets, and different
+/// labels yield different `a`.
+#[test]
+fn test_key_gen_with_label() {
+    let rng = &mut rng();
+    let (ek1, dk1) = key_gen_with_label::<StandardZq, 256>(b"protocol-1", rng);
+    let (ek2, dk2) = key_gen_with_label::<StandardZq, 256>(b"protocol-1", rng);
+    let (ek3, _) = key_gen_with_label::<StandardZq, 256>(b"protocol-2", rng);
+
+    assert!(ek1.same_crs(&ek2));
+    assert_ne!(dk1, dk2);
+    assert!(!ek1.same_crs(&ek3));
+
+    let message = Message::random(rng, 256);
+    let c = ek2.encrypt(rng, message.clone());
+    assert_eq!(message.data(), dk2.decrypt(c));
+}
+
+/// Test that the self test passes for a good key pair and fails for mismatched keys.
+#[test]
+fn test_key_pair_self_test() {
+    use rlwe_encryption::KeyPair;
+
+    let rng = &mut rng();
+    let key_pair = KeyPair::from(standard(rng));
+    let (_, other_dk) = standard(rng);
+
+    let message = Message::random(rng, 256);
+    assert!(key_pair.self_test(rng, message.clone()));
+
+    let mismatched = KeyPair::new(key_pair.ek, other_dk);
+    assert!(!mismatched.self_test(rng, message));
+}
+
+/// Test that auditable encryption is reproducible from the transcript.
+#[test]
+fn test_encrypt_auditable() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 256);
+
+    let c1 = ek.encrypt_auditable(message.clone(), b"transcript-1");
+    let c2 = ek.encrypt_auditable(message.clone(), b"transcript-1");
+    let c3 = ek.encrypt_auditable(message.clone(), b"transcript-2");
+    assert_eq!(c1, c2);
+    assert_ne!(c1, c3);
+    assert_eq!(message.data(), dk.decrypt(c1));
+}
+
+/// Test that the one-hot encryption decrypts to a single 1 at the given index.
+#[test]
+fn test_encrypt_one_hot() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    for index in [0, 17, 99] {
+        let c = ek.encrypt_one_hot(rng, index, 100);
+        let m = dk.decrypt_exact(c, 100);
+        assert_eq!(m.iter().sum::<i32>(), 1);
+        assert_eq!(m[index], 1);
+    }
+}
+
+/// Test that two encryptions of the same message differ in most coefficients while
+/// decrypting identically.
+#[test]
+fn test_coefficient_diff_count() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let message = Message::random(rng, 256);
+    let c1 = ek.encrypt(rng, message.clone());
+    let c2 = ek.encrypt(rng, message.clone());
+
+    assert_eq!(c1.coefficient_diff_count(&c1), 0);
+    assert!(c1.coefficient_diff_count(&c2) > 2 * 256 * 9 / 10);
+    assert_eq!(dk.decrypt(c1), dk.decrypt(c2));
+}
+
+/// Test that the decryption noise of the standard parameters is well below `q/4`.
+#[test]
+fn test_noise_samples() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let samples = dk.noise_samples(&ek, rng, 20);
+    assert_eq!(samples.len(), 20);
+    let max = samples
+        .iter()
+        .flat_map(|p| p.iter().map(|c| c.abs()))
+        .max()
+        .unwrap();
+    assert!(max < StandardZq::Q / 8, "max noise = {}", max);
+}
+
+/// Test that the optimized reduction of the standard field agrees with the generic
+/// `rem_euclid` based reduction.
+#[test]
+fn test_standard_modulo() {
+    let reference = |x: i32| {
+        let a = x.rem_euclid(StandardZq::Q);
+        if a > StandardZq::Q / 2 {
+            a - StandardZq::Q
+        } else {
+            a
+        }
+    };
+
+    for x in (-3_000_000..=3_000_000).chain([i32::MIN + 1, i32::MAX, i32::MIN]) {
+        assert_eq!(StandardZq::modulo(&x), reference(x), "x = {}", x);
+    }
+}
+
+/// Test that seeded key generation rejects low-entropy seeds and is deterministic otherwise.
+#[test]
+fn test_key_gen_from_seed() {
+    let rng = &mut rng();
+
+    assert_eq!(
+        key_gen_from_seed::<StandardZq, 256>([0u8; 32]),
+        Err(Error::LowEntropySeed)
+    );
+    assert!(key_gen_from_seed::<StandardZq, 256>([0xab; 32]).is_err());
+
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    let (ek, dk) = key_gen_from_seed::<StandardZq, 256>(seed).unwrap();
+    let (ek2, dk2) = key_gen_from_seed::<StandardZq, 256>(seed).unwrap();
+    assert_eq!(ek, ek2);
+    assert_eq!(dk, dk2);
+
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+    assert_eq!(message.data(), dk.decrypt(c));
+}
+
+/// Test that a strict failure target requires a larger `N` than a lenient one, and that
+/// the recommended parameters are valid.
+#[test]
+fn test_recommend_params() {
+    let lenient = recommend_params(2f64.powi(-20), 128.0).unwrap();
+    let strict = recommend_params(2f64.powi(-256), 128.0).unwrap();
+    assert!(lenient.n < strict.n);
+
+    for params in [lenient, strict] {
+        assert!(check_params(params.n, params.q, params.b).is_ok());
+        assert!(params.security_bits >= 128.0);
+    }
+    assert!(check_params(300, 3329, 1).is_err());
+    assert!(check_params(256, 3328, 1).is_err());
+    // the largest primes, whose trial division would overflow
+    assert!(check_params(256, 18446744073709551557, 1).is_ok());
+    assert!(check_params(256, 18446744073709551559, 1).is_err());
+    // a strong pseudoprime to the bases 2, 3, 5 and 7
+    assert!(check_params(256, 3215031751, 1).is_err());
+
+    assert!(recommend_params(0.0, 128.0).is_none());
+    assert!(recommend_params(-1.0, 128.0).is_none());
+    assert!(recommend_params(f64::NAN, 128.0).is_none());
+}
+
+/// Test that the exported test vectors are deterministic, and recomputing them from the
+/// re-imported keys, messages and randomness matches the exported values.
+#[cfg(feature = "json")]
+#[test]
+fn test_export_test_vectors() {
+    use rlwe_encryption::{export_test_vectors, TestVectors};
+
+    let seed = [42u8; 32];
+    let json = export_test_vectors::<StandardZq, 256>(seed, 5);
+    assert_eq!(json, export_test_vectors::<StandardZq, 256>(seed, 5));
+
+    let vectors = TestVectors::from_json(&json).unwrap();
+    assert_eq!((vectors.n, vectors.q, vectors.b), (256, 3329, 1));
+    assert_eq!(vectors.vectors.len(), 5);
+    for tv in &vectors.vectors {
+        assert!(tv.u.iter().chain(tv.v.iter()).all(|&c| c < 3329));
+        assert_eq!(tv.decryption, tv.message);
+    }
+    assert!(vectors.verify::<StandardZq, 256>());
+
+    // tampering with a ciphertext is detected
+    let mut tampered = vectors.clone();
+    tampered.vectors[0].v[0] = (tampered.vectors[0].v[0] + 1) % 3329;
+    assert!(!tampered.verify::<StandardZq, 256>());
+}
+
+/// Test that equal ciphertexts share the same id, while distinct ciphertexts do not.
+#[test]
+fn test_ciphertext_id() {
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c1 = ek.encrypt(rng, m.clone());
+    let c2 = ek.encrypt(rng, m);
+    assert_eq!(c1.id(), c1.clone().id());
+    assert_ne!(c1.id(), c2.id());
+}
+
+/// Test that the ciphertexts re-encrypted in batch decrypt correctly under the new key.
+#[test]
+fn test_reencrypt_batch() {
+    use rlwe_encryption::{reencrypt_batch, ReKey};
+
+    define_zq_i64!(ZqI64_8383489, 8383489);
+
+    let rng = &mut rng();
+    let (old_ek, old_dk) = key_gen::<ZqI64_8383489, 512>(rng);
+    let (new_ek, new_dk) = key_gen::<ZqI64_8383489, 512>(rng);
+    let rekey = ReKey::new(rng, &old_dk, &new_ek);
+
+    let messages = (0..5)
+        .map(|_| Message::random(rng, 512))
+        .collect::<Vec<_>>();
+    let cs = messages
+        .iter()
+        .map(|m| old_ek.encrypt(rng, m.clone()))
+        .collect();
+
+    let new_cs = reencrypt_batch(&rekey, cs);
+    assert_eq!(new_cs.len(), messages.len());
+    for (m, c) in messages.into_iter().zip(new_cs) {
+        assert_eq!(new_dk.decrypt(c), m.data());
+    }
+}
+
+/// Test that the NTT agrees with the schoolbook multiplication on an NTT-friendly field,
+/// and is reported as unavailable on the standard field (3329 != 1 mod 512).
+#[cfg(feature = "test-utils")]
+#[test]
+fn test_verify_ntt_correctness() {
+    use rlwe_encryption::verify_ntt_correctness;
+
+    define_zq_i64!(ZqI64_8383489, 8383489);
+
+    let rng = &mut rng();
+    assert!(verify_ntt_correctness::<ZqI64_8383489, 512>(rng, 5));
+    assert!(verify_ntt_correctness::<ZqI64_8383489, 256>(rng, 5));
+    assert!(!verify_ntt_correctness::<StandardZq, 256>(rng, 1));
+}
+
+/// Test that a blinded ciphertext decrypts to the original message after unblinding.
+#[test]
+fn test_blind_unblind() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+
+    let mut mask = Vec::new();
+    let blinded = c.blind(rng, &mut mask);
+    assert_eq!(mask.len(), 256);
+    assert_eq!(dk.unblind(blinded, &mask), m.data());
+
+    // the blinded ciphertext alone decrypts to the masked message
+    let blinded = c.blind(rng, &mut mask);
+    let masked = dk.decrypt(blinded);
+    assert_ne!(masked, dk.decrypt(c));
+}
+
+/// Test that the minimal modulus satisfies the parameter condition, as does the standard one.
+#[test]
+fn test_min_modulus_for() {
+    use rlwe_encryption::{min_modulus_for, min_prime_modulus_for};
+
+    let q = min_modulus_for(256, 1).unwrap();
+    assert!(q as f64 / 4.0 > 513.0);
+    assert!((q - 1) as f64 / 4.0 <= 513.0);
+    assert!(StandardZq::Q as u64 >= q);
+
+    let q_prime = min_prime_modulus_for(256, 1, false).unwrap();
+    assert!(q_prime >= q && check_params(256, q_prime, 1).is_ok());
+    let q_ntt = min_prime_modulus_for(256, 1, true).unwrap();
+    assert!(q_ntt >= q && q_ntt % 512 == 1 && check_params(256, q_ntt, 1).is_ok());
+
+    // the minimum 4 * (2 * 4 * 2^2 + 2) + 1 = 137 is 1 mod 8 and a prime itself
+    assert_eq!(min_prime_modulus_for(4, 2, true), Some(137));
+    assert_eq!(min_modulus_for(1 << 40, 1 << 20), None);
+    assert_eq!(min_prime_modulus_for(1 << 40, 1 << 20, true), None);
+}
+
+/// Test that summing the maximum number of ciphertexts still decrypts to the XOR of the messages.
+#[test]
+fn test_max_homomorphic_additions() {
+    use rlwe_encryption::max_homomorphic_additions;
+
+    let max = max_homomorphic_additions::<StandardZq, 256>();
+    assert!(max > 1 && max < 1000, "max = {}", max);
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let mut expected = vec![0; 256];
+    let mut sum = ek.encrypt(rng, Message::new(vec![]));
+    for _ in 1..max {
+        let message = Message::random(rng, 256);
+        let c = ek.encrypt(rng, message.clone());
+        expected
+            .iter_mut()
+            .zip(message.data())
+            .for_each(|(e, m)| *e ^= m);
+        sum = sum.add_ciphertext(&c);
+    }
+    assert_eq!(dk.decrypt(sum), expected);
+}
+
+/// Test that a message with a length not aligned to bytes round-trips exactly through
+/// its byte representation, and the length header is sufficient to trim the decrypted bits.
+#[test]
+fn test_message_bytes_non_byte_aligned() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let message = Message::random(rng, 13);
+    let bytes = message.to_bytes();
+    // 4 bytes header + 2 bytes of packed bits
+    assert_eq!(bytes.len(), 6);
+
+    let decoded = Message::from_bytes(&bytes).unwrap();
+    assert_eq!(message, decoded);
+
+    let c = ek.encrypt(rng, decoded.clone());
+    let m = dk.decrypt_exact(c, decoded.len());
+    assert_eq!(message.data(), m);
+
+    // truncated payload and oversized length are rejected
+    assert!(Message::<StandardZq, 256>::from_bytes(&bytes[..5]).is_err());
+    assert!(Message::<StandardZq, 256>::from_bytes(&257u32.to_le_bytes()).is_err());
+}
+
+/// Test that the sizes computed upfront match the actual compact serialization.
+#[test]
+fn test_compact_sizes() {
+    use rlwe_encryption::{
+        ciphertext_size, decrypt_key_size, encrypt_key_size, CipherText, DecryptKey, EncryptKey,
+    };
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message);
+
+    let ek_bytes = ek.to_compact();
+    assert_eq!(ek_bytes.len(), encrypt_key_size::<StandardZq, 256>());
+    let ek2 = EncryptKey::<StandardZq, 256>::from_compact(&ek_bytes).unwrap();
+    assert_eq!(ek_bytes, ek2.to_compact());
+
+    let dk_bytes = dk.to_compact();
+    assert_eq!(dk_bytes.len(), decrypt_key_size::<StandardZq, 256>());
+    assert_eq!(dk, DecryptKey::from_compact(&dk_bytes).unwrap());
+
+    let c_bytes = c.to_compact();
+    assert_eq!(c_bytes.len(), ciphertext_size::<StandardZq, 256>());
+    let c2 = CipherText::<StandardZq, 256>::from_compact(&c_bytes).unwrap();
+    assert_eq!(c_bytes, c2.to_compact());
+
+    // the secret must be small
+    assert_eq!(
+        DecryptKey::<StandardZq, 256>::from_compact(&ek_bytes[..384]),
+        Err(Error::InvalidKeyBytes)
+    );
+}
+
+/// Test that both bit orders round-trip, and differ for a non-palindromic byte.
+#[test]
+fn test_message_bytes_bit_order() {
+    use rlwe_encryption::BitOrder;
+
+    let message = Message::<StandardZq, 256>::new(vec![1, 1, 0, 0, 0, 0, 0, 0, 1]);
+    let lsb = message.to_bytes_with_order(BitOrder::LsbFirst);
+    let msb = message.to_bytes_with_order(BitOrder::MsbFirst);
+    assert_eq!(lsb, message.to_bytes());
+    assert_eq!(lsb[4..], [0b0000_0011, 0b0000_0001]);
+    assert_eq!(msb[4..], [0b1100_0000, 0b1000_0000]);
+
+    for order in [BitOrder::LsbFirst, BitOrder::MsbFirst] {
+        let bytes = message.to_bytes_with_order(order);
+        assert_eq!(
+            message,
+            Message::from_bytes_with_order(&bytes, order).unwrap()
+        );
+    }
+    assert_ne!(
+        message,
+        Message::from_bytes_with_order(&lsb, BitOrder::MsbFirst).unwrap()
+    );
+}
+
+/// Test that the TLV serialization skips unknown records and rejects missing ones.
+#[test]
+fn test_ciphertext_tlv() {
+    use rlwe_encryption::CipherText;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+
+    let bytes = c.to_tlv();
+    // two records of 5 bytes header and 384 bytes value
+    assert_eq!(bytes.len(), 2 * (5 + 384));
+    assert_eq!(CipherText::from_tlv(&bytes).unwrap(), c);
+
+    // an unknown record before `u` and after `v`
+    let unknown = [&[0x7f][..], &3u32.to_le_bytes(), b"abc"].concat();
+    let extended = [&unknown[..], &bytes, &unknown].concat();
+    let parsed = CipherText::<StandardZq, 256>::from_tlv(&extended).unwrap();
+    assert_eq!(dk.decrypt(parsed), m.data());
+
+    let invalid = [
+        &bytes[..5 + 384],
+        &bytes[..bytes.len() - 1],
+        &[&bytes[..], &bytes[..5 + 384]].concat(),
+    ];
+    for invalid in invalid {
+        assert_eq!(
+            CipherText::<StandardZq, 256>::from_tlv(invalid),
+            Err(Error::InvalidCipherTextBytes)
+        );
+    }
+}
+
+/// Test that raw bytes round-trip in both bit orders, through the message and the encryption.
+#[test]
+fn test_message_raw_bytes() {
+    use rlwe_encryption::BitOrder;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let slices: [&[u8]; 4] = [&[], &[0xa5], b"hello, world", &[0xff; 32]];
+    for order in [BitOrder::LsbFirst, BitOrder::MsbFirst] {
+        for bytes in slices {
+            let message = Message::<StandardZq, 256>::from_raw_bytes(bytes, order).unwrap();
+            assert_eq!(message.len(), bytes.len() * 8);
+            assert_eq!(message.to_raw_bytes(order), bytes);
+
+            let c = ek.encrypt(rng, message.clone());
+            let decrypted = Message::<StandardZq, 256>::new(dk.decrypt_exact(c, message.len()));
+            assert_eq!(decrypted.to_raw_bytes(order), bytes);
+        }
+
+        // 13 bits are padded with zeros to 2 bytes
+        let message = Message::<StandardZq, 256>::new(vec![1; 13]);
+        let bytes = message.to_raw_bytes(order);
+        assert_eq!(bytes.len(), 2);
+        let padded = Message::<StandardZq, 256>::from_raw_bytes(&bytes, order).unwrap();
+        assert_eq!(padded.data()[..13], message.data());
+    }
+    assert_eq!(
+        Message::<StandardZq, 256>::new(vec![1; 13]).to_raw_bytes(BitOrder::MsbFirst),
+        [0xff, 0b1111_1000]
+    );
+
+    assert_eq!(
+        Message::<StandardZq, 256>::from_raw_bytes(&[0; 33], BitOrder::LsbFirst),
+        Err(Error::InvalidMessage)
+    );
+}
+
+/// Test that decrypting to a fixed-size array gives the same result as decrypting to a vector.
+#[test]
+fn test_decrypt_array() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    for _ in 0..10 {
+        let message = Message::random(rng, 200);
+        let c = ek.encrypt(rng, message.clone());
+        let m_array = dk.decrypt_array(c.clone());
+        let m_vec = dk.decrypt(c);
+        assert_eq!(m_array.len(), 256);
+        assert_eq!(m_array.to_vec(), m_vec);
+        assert_eq!(message.data(), m_array[..200].to_vec());
+    }
+}
+
 #[test]
 #[cfg(feature = "serde")]
 fn test_serde() {
@@ -99,3 +649,1835 @@ fn test_serde() {
     let deserialized_c = bincode::deserialize(&serialized_c).unwrap();
     assert_eq!(c, deserialized_c);
 }
+
+/// Test that the negation of a ciphertext decrypts to the same message, since -1 = 1 in
+/// the binary plaintext space, and that a ciphertext added to its negation decrypts to zeros.
+#[test]
+fn test_ciphertext_neg() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let expected = m.clone().data();
+    let c = ek.encrypt(rng, m);
+
+    assert_eq!(dk.decrypt(-c.clone()), expected);
+    assert_eq!(dk.decrypt(-(-c.clone())), expected);
+    assert_eq!(-(-c.clone()), c);
+    assert_eq!(dk.decrypt(c.clone() + (-c)), vec![0; 256]);
+}
+
+/// Test that the compact serde wrapper roundtrips and is smaller than the default format.
+#[test]
+#[cfg(feature = "serde")]
+fn test_compact_ciphertext_serde() {
+    use rlwe_encryption::CompactCipherText;
+
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message);
+
+    let serialized = bincode::serialize(&c).unwrap();
+    let serialized_compact = bincode::serialize(&CompactCipherText(c.clone())).unwrap();
+    // 8 bytes for the length, followed by 2 * 256 * 12 / 8 = 768 bytes
+    assert_eq!(serialized_compact.len(), 8 + 768);
+    assert!(serialized_compact.len() < serialized.len());
+
+    let deserialized: CompactCipherText<_, 256> =
+        bincode::deserialize(&serialized_compact).unwrap();
+    assert_eq!(deserialized.0, c);
+    assert!(bincode::deserialize::<CompactCipherText<StandardZq, 256>>(&serialized).is_err());
+}
+
+/// Test that all reduction strategies agree with the modulo operation, and that a field using
+/// them encrypts and decrypts correctly.
+#[test]
+fn test_reduction_strategies() {
+    define_zq_i64!(ZqModulo, 3329);
+    define_zq_i64!(ZqBarrett, 3329, Reduction::Barrett);
+    define_zq_i64!(ZqMontgomery, 3329, Reduction::Montgomery);
+
+    let q = 3329i64;
+    let values = (-2 * q * q..=2 * q * q)
+        .step_by(7)
+        .chain(-4 * q..=4 * q)
+        .chain([i64::MIN + 1, i64::MAX, -(1 << 40), 1 << 40]);
+    for x in values {
+        let expected = ZqModulo::modulo(&x);
+        assert_eq!(rlwe_encryption::reduce::<ZqModulo>(&x), expected);
+        assert_eq!(rlwe_encryption::reduce::<ZqBarrett>(&x), expected);
+        assert_eq!(rlwe_encryption::reduce::<ZqMontgomery>(&x), expected);
+    }
+
+    let rng = &mut rng();
+    let (ek, dk) = key_gen::<ZqMontgomery, 256>(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+    assert_eq!(dk.decrypt(c), message.data());
+}
+
+/// Test that the keys roundtrip through their parts, and that invalid lengths are rejected.
+#[test]
+fn test_key_from_parts() {
+    use rlwe_encryption::KeyError;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let (a, t) = ek.parts();
+    assert_eq!((a.len(), t.len()), (256, 256));
+    let ek2 = EncryptKey::<StandardZq, 256>::from_parts(a.clone(), t).unwrap();
+    assert_eq!(ek2, ek);
+
+    let s = dk.secret();
+    assert_eq!(s.len(), 256);
+    let dk2 = DecryptKey::<StandardZq, 256>::from_parts(s.to_vec()).unwrap();
+    assert_eq!(dk2, dk);
+
+    // coefficients are reduced modulo q
+    let shifted = a.iter().map(|ai| ai + 3329).collect::<Vec<_>>();
+    let (a2, _) = EncryptKey::<StandardZq, 256>::from_parts(shifted, a.clone())
+        .unwrap()
+        .parts();
+    assert_eq!(a2, a);
+
+    assert_eq!(
+        DecryptKey::<StandardZq, 256>::from_parts(vec![0; 255]).unwrap_err(),
+        KeyError::InvalidLength {
+            expected: 256,
+            actual: 255
+        }
+    );
+    assert_eq!(
+        EncryptKey::<StandardZq, 256>::from_parts(a, vec![0; 257]).unwrap_err(),
+        KeyError::InvalidLength {
+            expected: 256,
+            actual: 257
+        }
+    );
+}
+
+/// Test that ciphertext addition and plaintext multiplication match the GF(2) polynomial
+/// operations on the messages.
+#[test]
+fn test_gf2_polynomials() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m1 = Message::random(rng, 256);
+    let m2 = Message::random(rng, 200);
+    let sum = m1.gf2_add(&m2);
+    assert_eq!(sum.len(), 256);
+
+    let c1 = ek.encrypt(rng, m1.clone());
+    let c2 = ek.encrypt(rng, m2);
+    assert_eq!(dk.decrypt(c1.add_ciphertext(&c2)), sum.data());
+
+    // (1 + X)^2 = 1 + X^2, and X^255 * X = X^256 = 1 over GF(2) modulo X^256 + 1
+    let one_plus_x = Message::<StandardZq, 256>::new(vec![1, 1]);
+    assert_eq!(one_plus_x.gf2_mul(&one_plus_x).data()[..3], [1, 0, 1]);
+    let mut x255 = vec![0; 256];
+    x255[255] = 1;
+    let x = Message::<StandardZq, 256>::new(vec![0, 1]);
+    let mut one = vec![0; 256];
+    one[0] = 1;
+    assert_eq!(Message::new(x255).gf2_mul(&x).data(), one);
+
+    let p = Message::new(vec![1, 0, 1]);
+    let product = m1.gf2_mul(&p);
+    assert_eq!(dk.decrypt(c1.mul_plaintext(&p.data())), product.data());
+}
+
+/// Test that applying the delta to the base ciphertext reconstructs the modified one.
+#[test]
+fn test_ciphertext_diff() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let base = ek.encrypt(rng, m);
+    let modified = base.add_plaintext(Message::new(vec![1, 0, 0, 1]));
+
+    let delta = base.diff(&modified);
+    assert_eq!(delta.len(), 2);
+    assert_eq!(base.apply_diff(&delta).unwrap(), modified);
+    assert!(base.diff(&base).is_empty());
+
+    // a re-encryption changes almost all coefficients, but is still reconstructed
+    let m = Message::random(rng, 256);
+    let other = ek.encrypt(rng, m.clone());
+    let reconstructed = base.apply_diff(&base.diff(&other)).unwrap();
+    assert_eq!(reconstructed, other);
+    assert_eq!(dk.decrypt(reconstructed), m.data());
+}
+
+/// Test that the weighted sum of ciphertexts decrypts to the weighted sum of the messages
+/// modulo 2.
+#[test]
+fn test_weighted_sum() {
+    use rlwe_encryption::{try_weighted_sum, weighted_sum};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let weights = [1, 2, 3, -1];
+    let messages = (0..weights.len())
+        .map(|_| Message::<StandardZq, 256>::random(rng, 256).data())
+        .collect::<Vec<_>>();
+    let cs = messages
+        .iter()
+        .map(|m| ek.encrypt(rng, Message::new(m.clone())))
+        .collect::<Vec<_>>();
+
+    let expected = (0..256)
+        .map(|j| {
+            weights
+                .iter()
+                .zip(&messages)
+                .map(|(w, m)| w * m[j])
+                .sum::<i32>()
+                .rem_euclid(2)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(dk.decrypt(weighted_sum(&cs, &weights)), expected);
+    assert_eq!(
+        try_weighted_sum(&cs, &weights[..3]).unwrap_err(),
+        Error::InvalidLength
+    );
+}
+
+/// Test that multiplying by a cleartext bit selects the message or zeros, and that the
+/// scalar multiplication decrypts to `k * m mod 2`.
+#[test]
+fn test_mul_plain_bit() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+    assert_eq!(
+        dk.decrypt(c.clone().mul_plain_bit(true)),
+        message.clone().data()
+    );
+    assert_eq!(dk.decrypt(c.clone().mul_plain_bit(false)), vec![0; 256]);
+
+    assert_eq!(dk.decrypt(c.clone().mul_plain_scalar(2)), vec![0; 256]);
+    assert_eq!(dk.decrypt(c.mul_plain_scalar(-1)), message.data());
+}
+
+/// Test that a non-binary message panics with the `checked` feature.
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "message must be binary")]
+fn test_checked_message_not_binary() {
+    let _ = Message::<StandardZq, 256>::new(vec![0, 1, 2]);
+}
+
+/// Test that a message longer than `N` panics with the `checked` feature.
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "length at most N")]
+fn test_checked_message_too_long() {
+    let _ = Message::<StandardZq, 256>::new(vec![0; 257]);
+}
+
+/// Test that encrypting an invalid message bypassing [Message::new] (e.g. deserialized)
+/// panics with the `checked` feature.
+#[cfg(all(feature = "checked", feature = "serde"))]
+#[test]
+#[should_panic(expected = "message must be binary")]
+fn test_checked_encrypt() {
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    let bytes = bincode::serialize(&vec![0i32, 3]).unwrap();
+    let message: Message<StandardZq, 256> = bincode::deserialize(&bytes).unwrap();
+    let _ = ek.encrypt(rng, message);
+}
+
+/// Test that the noise of fresh ciphertexts stays well under the decryption margin with the
+/// standard parameters, and reaches it with the too small modulus `q = 7`.
+#[test]
+fn test_noise_magnitude() {
+    use rlwe_encryption::{decryption_margin, key_gen_with_label};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let margin = decryption_margin::<StandardZq, 256>();
+    for _ in 0..100 {
+        let m = Message::random(rng, 256);
+        let c = ek.encrypt(rng, m);
+        assert!(dk.noise_magnitude(&c) < margin / 4);
+    }
+
+    define_zq_i64!(ZqI64_7, 7);
+    let (ek, dk) = key_gen_with_label::<ZqI64_7, 4>(b"too small", rng);
+    let margin = decryption_margin::<ZqI64_7, 4>();
+    assert_eq!(margin, 1);
+    let max_noise = (0..100)
+        .map(|_| {
+            let m = Message::random(rng, 4);
+            dk.noise_magnitude(&ek.encrypt(rng, m))
+        })
+        .max()
+        .unwrap();
+    assert!(max_noise >= margin);
+}
+
+/// Test that field elements with a plaintext modulus larger than 2 roundtrip.
+#[test]
+fn test_encrypt_field_elements() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let c = ek.encrypt_field_elements(rng, vec![0, 1, 2, 3], 4).unwrap();
+    assert_eq!(dk.decrypt_field_elements(c, &4)[..4], [0, 1, 2, 3]);
+
+    let coeffs = (0..256).map(|i| i % 8).collect::<Vec<_>>();
+    let c = ek.encrypt_field_elements(rng, coeffs.clone(), 8).unwrap();
+    assert_eq!(dk.decrypt_field_elements(c, &8), coeffs);
+
+    assert!(ek.encrypt_field_elements(rng, vec![4], 4).is_err());
+    assert!(ek.encrypt_field_elements(rng, vec![-1], 4).is_err());
+    assert!(ek.encrypt_field_elements(rng, vec![0], 1).is_err());
+}
+
+/// Test that a fresh ciphertext has a high coefficient entropy, and structured ones lower.
+#[test]
+fn test_coefficient_entropy() {
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m);
+    // 512 samples from 3329 values, at most log2(512) = 9 bits
+    let entropy = c.coefficient_entropy();
+    assert!(entropy > 8.5 && entropy <= 9.0, "{entropy}");
+
+    let sparse =
+        rlwe_encryption::CipherText::<StandardZq, 256>::from_parts(vec![1, 0, 2], vec![3]).unwrap();
+    assert!(sparse.coefficient_entropy() < 0.2);
+    assert_eq!(c.mul_plain_bit(false).coefficient_entropy(), 0.0);
+}
+
+/// Test that a ciphertext and its re-randomization have a low coefficient correlation.
+#[test]
+fn test_ciphertext_correlation() {
+    use rlwe_encryption::ciphertext_correlation;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+    let zero = ek.encrypt(rng, Message::new(vec![0; 256]));
+    let rerandomized = c.add_ciphertext(&zero);
+    assert_eq!(dk.decrypt(rerandomized.clone()), m.clone().data());
+
+    let correlation = ciphertext_correlation(&c, &rerandomized);
+    assert!(correlation.abs() < 0.2, "{correlation}");
+    assert!((ciphertext_correlation(&c, &c) - 1.0).abs() < 1e-9);
+    // `u` is unchanged by adding a plaintext
+    assert!(ciphertext_correlation(&c, &c.add_plaintext(m)) > 0.4);
+    let zero_ciphertext = rerandomized.mul_plain_bit(false);
+    assert_eq!(ciphertext_correlation(&c, &zero_ciphertext), 0.0);
+}
+
+/// Test the `i64` preset with random 1024-bit messages.
+#[test]
+fn test_standard_i64() {
+    let rng = &mut rng();
+    let (ek, dk) = rlwe_encryption::standard_i64(rng);
+
+    for _ in 0..10 {
+        let message = Message::random(rng, 1024);
+        let c = ek.encrypt(rng, message.clone());
+        assert_eq!(dk.decrypt(c), message.data());
+    }
+}
+
+/// Test the bigint field `ZqBig` with a random 256-bit message.
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_field_round_trip() {
+    use rlwe_encryption::{try_key_gen, ZqBig};
+
+    let rng = &mut rng();
+    let (ek, dk) = try_key_gen::<ZqBig, 256>(rng).unwrap();
+    let m = Message::<ZqBig, 256>::random(rng, 256);
+    let expected = m.clone().data();
+    let c = ek.encrypt(rng, m);
+    assert_eq!(dk.decrypt(c), expected);
+}
+
+/// Test that zeroizing a bigint resets it to zero.
+#[cfg(all(feature = "bigint", feature = "zeroize"))]
+#[test]
+fn test_zeroize_bigint() {
+    use num::{One, Zero};
+    use rlwe_encryption::BigI;
+    use zeroize::Zeroize;
+
+    let mut x = BigI::from(num::BigInt::one() << 600) - BigI::one();
+    x.zeroize();
+    assert!(x.is_zero());
+}
+
+/// Test that encrypting a borrowed slice is the same as encrypting the owned message.
+#[test]
+fn test_encrypt_slice() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let buffer = (0..1024)
+        .map(|_| (rng.next_u32() % 2) as i32)
+        .collect::<Vec<_>>();
+    for chunk in buffer.chunks(256) {
+        let c = ek.encrypt_slice(rng, chunk);
+        let c2 = ek.encrypt(rng, Message::new(chunk.to_vec()));
+        assert_eq!(dk.decrypt(c), chunk);
+        assert_eq!(dk.decrypt(c2), chunk);
+    }
+
+    // the same randomness gives the same ciphertext
+    let seed = [7u8; 32];
+    let m = &buffer[..100];
+    let c = ek.encrypt_slice(&mut StdRng::from_seed(seed), m);
+    let c2 = ek.encrypt(&mut StdRng::from_seed(seed), Message::new(m.to_vec()));
+    assert_eq!(c, c2);
+}
+
+/// Test that a session replayed from the same base seed reproduces identical ciphertexts,
+/// and that the ciphertexts within a session are distinct.
+#[test]
+fn test_session_replay() {
+    use rlwe_encryption::Session;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let mut base_seed = [0u8; 32];
+    rng.fill_bytes(&mut base_seed);
+
+    let messages = (0..5)
+        .map(|_| Message::<StandardZq, 256>::random(rng, 256))
+        .collect::<Vec<_>>();
+
+    let mut session = Session::new(ek.clone(), base_seed);
+    let transcript = messages
+        .iter()
+        .map(|m| session.encrypt_next(m.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(session.counter(), 5);
+
+    let mut replay = Session::new(ek.clone(), base_seed);
+    for (m, c) in messages.iter().zip(&transcript) {
+        assert_eq!(&replay.encrypt_next(m.clone()), c);
+        assert_eq!(dk.decrypt(c.clone()), m.clone().data());
+    }
+
+    // the same message gets distinct randomness at different counters
+    let mut session = Session::new(ek, base_seed);
+    let c1 = session.encrypt_next(messages[0].clone());
+    let c2 = session.encrypt_next(messages[0].clone());
+    assert_ne!(c1, c2);
+}
+
+/// Test that several short messages packed into one ciphertext are recovered.
+#[test]
+fn test_encrypt_packed() {
+    use rlwe_encryption::PackError;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let messages = [10, 50, 100]
+        .iter()
+        .map(|&len| Message::<StandardZq, 256>::random(rng, len).data())
+        .collect::<Vec<_>>();
+    let c = ek.encrypt_packed(rng, &messages).unwrap();
+    assert_eq!(dk.decrypt_packed(c, &[10, 50, 100]), messages);
+
+    let too_long = vec![vec![0; 200], vec![1; 57]];
+    assert_eq!(
+        ek.encrypt_packed(rng, &too_long).unwrap_err(),
+        PackError::TooLong { total: 257, n: 256 }
+    );
+    assert_eq!(
+        ek.encrypt_packed(rng, &[vec![0, 1], vec![1, 0, 2]])
+            .unwrap_err(),
+        PackError::NonBinary {
+            message: 1,
+            index: 2,
+            value: 2
+        }
+    );
+}
+
+/// Test that shuffled and retransmitted chunks are reassembled correctly.
+#[test]
+fn test_encrypt_chunked() {
+    use rand::seq::SliceRandom;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = (0..1000)
+        .map(|_| (rng.next_u32() % 2) as i32)
+        .collect::<Vec<_>>();
+    let chunks = ek.encrypt_chunked(rng, &m).unwrap();
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks.iter().all(|chunk| chunk.total == 4));
+
+    let mut shuffled = chunks.clone();
+    shuffled.push(chunks[2].clone());
+    shuffled.shuffle(rng);
+    assert_eq!(dk.decrypt_chunked(shuffled, 1000).unwrap(), m);
+
+    let mut missing = chunks.clone();
+    missing.remove(1);
+    assert_eq!(
+        dk.decrypt_chunked(missing, 1000).unwrap_err(),
+        Error::IncompleteChunks
+    );
+    assert_eq!(
+        dk.decrypt_chunked(chunks, 1025).unwrap_err(),
+        Error::InvalidLength
+    );
+    assert!(ek.encrypt_chunked(rng, &[0, 2]).is_err());
+
+    let empty = ek.encrypt_chunked(rng, &[]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(dk.decrypt_chunked(empty, 0).unwrap(), Vec::<i32>::new());
+    assert_eq!(
+        dk.decrypt_chunked(Vec::new(), 1).unwrap_err(),
+        Error::InvalidLength
+    );
+}
+
+/// Test that a random 1 KiB buffer roundtrips through byte-oriented encryption.
+#[test]
+fn test_encrypt_bytes() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let mut bytes = vec![0u8; 1024];
+    rng.fill_bytes(&mut bytes);
+    let cs = ek.encrypt_bytes(rng, &bytes);
+    assert_eq!(cs.len(), 32);
+    assert_eq!(dk.decrypt_bytes(&cs, bytes.len()), bytes);
+
+    // The last chunk is padded, and the padding is removed on decryption.
+    let cs = ek.encrypt_bytes(rng, &bytes[..100]);
+    assert_eq!(cs.len(), 4);
+    assert_eq!(dk.decrypt_bytes(&cs, 100), &bytes[..100]);
+
+    let (small_ek, _) = key_gen::<StandardZq, 4>(rng);
+    assert_eq!(
+        small_ek.try_encrypt_bytes(rng, &bytes).err(),
+        Some(Error::InvalidParams)
+    );
+}
+
+/// Test that zeroizing a decryption key wipes all coefficients of the secret.
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_zeroize_decrypt_key() {
+    use zeroize::Zeroize;
+
+    let rng = &mut rng();
+    let (_, mut dk) = standard(rng);
+    assert!(dk.to_compact().iter().any(|&b| b != 0));
+
+    dk.zeroize();
+    assert!(dk.to_compact().iter().all(|&b| b == 0));
+}
+
+/// Test that the homomorphic NOT decrypts to the complemented message.
+#[test]
+fn test_ciphertext_not() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let expected = m.clone().data().iter().map(|b| 1 - b).collect::<Vec<_>>();
+    let c = ek.encrypt(rng, m);
+    assert_eq!(dk.decrypt(c.not()), expected);
+}
+
+/// Test that encapsulation and decapsulation agree on the shared secret.
+#[cfg(feature = "kem")]
+#[test]
+fn test_kem() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let mut secrets = std::collections::HashSet::new();
+    for _ in 0..100 {
+        let (c, secret) = ek.encapsulate(rng);
+        assert_eq!(dk.decapsulate(&c), secret);
+        secrets.insert(secret);
+    }
+    assert_eq!(secrets.len(), 100);
+}
+
+/// Test that both parties derive the same channel key for the same ciphertext and context.
+#[cfg(feature = "kem")]
+#[test]
+fn test_derive_channel_key() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let context = b"rlwe-channel v1: alice -> bob";
+    let (c, key) = ek.encapsulate_channel_key(rng, context);
+    assert_eq!(dk.derive_channel_key(&c, context), key);
+    assert_eq!(dk.derive_channel_key(&c, context), key);
+
+    assert_ne!(
+        dk.derive_channel_key(&c, b"rlwe-channel v1: bob -> alice"),
+        key
+    );
+    assert_ne!(dk.decapsulate(&c), key);
+}
+
+/// Test that `try_encrypt` rejects over-length and non-binary messages, and encrypts a
+/// valid one.
+#[test]
+fn test_try_encrypt() {
+    use rlwe_encryption::EncryptError;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::<StandardZq, 256>::random(rng, 256).data();
+    let c = ek.try_encrypt(rng, m.clone()).unwrap();
+    assert_eq!(dk.decrypt(c), m);
+
+    assert_eq!(
+        ek.try_encrypt(rng, vec![0; 257]).unwrap_err(),
+        EncryptError::TooLong { len: 257, n: 256 }
+    );
+    assert_eq!(
+        ek.try_encrypt(rng, vec![0, 1, 1, -1, 2]).unwrap_err(),
+        EncryptError::NonBinary {
+            index: 3,
+            value: -1
+        }
+    );
+    // the same validation as `Message::try_new`
+    assert_eq!(
+        Message::<StandardZq, 256>::try_new(vec![0, 1, 1, -1, 2]),
+        Err(Error::InvalidMessage)
+    );
+}
+
+/// Test that the proof of a correct decryption verifies, while a false claim cannot be
+/// proven, and the proof fails for a false claim or for another ciphertext.
+#[test]
+fn test_decryption_proof() {
+    use rlwe_encryption::{prove_decryption, verify_decryption};
+
+    let rng = &mut rng();
+    let (ek, dk) = rlwe_encryption::standard_i64(rng);
+
+    let m = Message::random(rng, 1024);
+    let c = ek.encrypt(rng, m.clone());
+    let proof = prove_decryption(&ek, &dk, &c, &m, rng).unwrap();
+    assert!(verify_decryption(&ek, &c, &m, &proof));
+
+    let mut false_data = m.clone().data();
+    false_data[7] = 1 - false_data[7];
+    let false_m = Message::new(false_data);
+    assert!(!verify_decryption(&ek, &c, &false_m, &proof));
+    assert!(prove_decryption(&ek, &dk, &c, &false_m, rng).is_none());
+
+    let other = ek.encrypt(rng, m.clone());
+    assert!(!verify_decryption(&ek, &other, &m, &proof));
+}
+
+/// Test that the proof of a bit encryption verifies for both bits, while a non-bit plaintext
+/// cannot be proven and a proof does not verify for a ciphertext of a non-bit plaintext.
+#[test]
+fn test_bit_proof() {
+    use rlwe_encryption::{prove_bit, verify_bit};
+
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    for bit in [0, 1] {
+        let (c, opening) = ek.encrypt_with_opening(rng, Message::new(vec![bit]));
+        let proof = prove_bit(&ek, &c, &opening, rng).unwrap();
+        assert!(verify_bit(&ek, &c, &proof));
+
+        // [bit, 1] is not a single bit
+        let non_bit = c.add_plaintext(Message::new(vec![0, 1]));
+        assert!(!verify_bit(&ek, &non_bit, &proof));
+    }
+
+    let (c, opening) = ek.encrypt_with_opening(rng, Message::new(vec![0, 1]));
+    assert!(prove_bit(&ek, &c, &opening, rng).is_none());
+    let (other, _) = ek.encrypt_with_opening(rng, Message::new(vec![1]));
+    assert!(prove_bit(&ek, &other, &opening, rng).is_none());
+}
+
+/// Test that the verified partial decryptions of three parties combine into the message,
+/// while a corrupted partial decryption, or one computed with another share, is rejected
+/// with the index of the party.
+#[test]
+fn test_combine_partials_verified() {
+    use rlwe_encryption::{
+        combine_partials_verified, joint_encrypt_key, partial_decrypt, Polynomial,
+    };
+
+    let rng = &mut rng();
+    let (ek0, dk0) = rlwe_encryption::standard_i64(rng);
+    let (ek1, dk1) = key_gen_from_crs(&ek0, rng);
+    let (ek2, dk2) = key_gen_from_crs(&ek0, rng);
+    let commitments = [ek0, ek1, ek2];
+    let shares = [dk0, dk1, dk2];
+    let ek = joint_encrypt_key(&commitments).unwrap();
+
+    let m = Message::random(rng, 1024);
+    let c = ek.encrypt(rng, m.clone());
+    let partials = commitments
+        .iter()
+        .zip(&shares)
+        .map(|(ek_i, dk_i)| partial_decrypt(ek_i, dk_i, &c, rng).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        combine_partials_verified(&commitments, &c, &partials),
+        Ok(m.data())
+    );
+
+    // shift a coefficient of the partial decryption of party 1 by q/2
+    let mut corrupted = partials.clone();
+    corrupted[1].d = corrupted[1].d.clone() + Polynomial::new(vec![0, 8380417]);
+    assert_eq!(
+        combine_partials_verified(&commitments, &c, &corrupted),
+        Err(Error::InvalidPartialDecryption { index: 1 })
+    );
+
+    // the partial decryption of party 2 does not verify against the commitment of party 1
+    let mut swapped = partials.clone();
+    swapped[1] = partials[2].clone();
+    assert_eq!(
+        combine_partials_verified(&commitments, &c, &swapped),
+        Err(Error::InvalidPartialDecryption { index: 1 })
+    );
+
+    assert_eq!(
+        combine_partials_verified(&commitments, &c, &partials[..2]),
+        Err(Error::InvalidLength)
+    );
+}
+
+/// Test that the parallel batch encryption decrypts to the same messages as a sequential
+/// loop, in the same order, and is reproducible from the seed.
+#[cfg(feature = "rayon")]
+#[test]
+fn test_encrypt_batch_par() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let messages = (0..100)
+        .map(|i| Message::<StandardZq, 256>::random(rng, 1 + i % 20).data())
+        .collect::<Vec<_>>();
+    let sequential = messages
+        .iter()
+        .map(|m| dk.decrypt_exact(ek.encrypt_slice(rng, m), m.len()))
+        .collect::<Vec<_>>();
+
+    let seed = [3u8; 32];
+    let cs = ek.encrypt_batch_par(&messages, seed);
+    let parallel = cs
+        .iter()
+        .zip(messages.iter())
+        .map(|(c, m)| dk.decrypt_exact(c.clone(), m.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(parallel, sequential);
+    assert_eq!(parallel, messages);
+
+    assert_eq!(ek.encrypt_batch_par(&messages, seed), cs);
+    assert_ne!(ek.encrypt_batch_par(&messages, [4u8; 32]), cs);
+}
+
+/// Test that a `standard` ciphertext switched down to the prime modulus 1601 decrypts
+/// correctly with the switched key, and is smaller.
+#[test]
+fn test_mod_switch() {
+    #[derive(Clone, Debug)]
+    struct ZqI32Q1601;
+
+    impl IntField for ZqI32Q1601 {
+        type I = i32;
+        const Q: i32 = 1601;
+        const B: i32 = 1;
+
+        fn modulo(x: &Self::I) -> Self::I {
+            let a = x.rem_euclid(Self::Q);
+            if a > Self::Q / 2 {
+                a - Self::Q
+            } else {
+                a
+            }
+        }
+    }
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let switched_dk = dk.mod_switch::<ZqI32Q1601>();
+
+    for _ in 0..20 {
+        let m = Message::random(rng, 256);
+        let c = ek.encrypt(rng, m.clone());
+        let switched = c.clone().mod_switch::<ZqI32Q1601>();
+        assert!(switched.to_compact().len() < c.to_compact().len());
+        assert_eq!(switched_dk.decrypt(switched), m.data());
+    }
+}
+
+/// Test the decryption at different thresholds, including the boundary `|x| == threshold`.
+#[test]
+fn test_decrypt_with_threshold() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+    let raw = dk.decrypt_raw(c.clone());
+
+    // q/4 is the default decision boundary
+    let default = dk.decrypt_with_threshold(c.clone(), &(StandardZq::Q / 4));
+    assert_eq!(default, m.data());
+    // every |x| <= Q/2
+    let zeros = dk.decrypt_with_threshold(c.clone(), &(StandardZq::Q / 2));
+    assert_eq!(zeros, vec![0; 256]);
+    let ones = dk.decrypt_with_threshold(c.clone(), &-1);
+    assert_eq!(ones, vec![1; 256]);
+
+    // 1 iff |x| > threshold
+    let x = raw[0].abs();
+    assert_eq!(dk.decrypt_with_threshold(c.clone(), &x)[0], 0);
+    assert_eq!(dk.decrypt_with_threshold(c.clone(), &(x - 1))[0], 1);
+
+    // the fresh noise is far from the boundary, so nothing is ambiguous within 100
+    let lower = dk.decrypt_with_threshold(c.clone(), &(StandardZq::Q / 4 - 100));
+    let upper = dk.decrypt_with_threshold(c, &(StandardZq::Q / 4 + 100));
+    assert_eq!(lower, upper);
+}
+
+/// Test that the fingerprint of a key is deterministic and changes with any coefficient.
+#[cfg(feature = "fingerprint")]
+#[test]
+fn test_encrypt_key_fingerprint() {
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    let restored = EncryptKey::<StandardZq, 256>::from_compact(&ek.to_compact()).unwrap();
+    assert_eq!(ek.fingerprint(), restored.fingerprint());
+    assert_eq!(ek.to_string(), restored.to_string());
+
+    let display = ek.to_string();
+    assert!(display.starts_with("EncryptKey(") && display.ends_with(')'));
+    assert_eq!(display.len(), "EncryptKey()".len() + 16);
+
+    let (a, mut t) = ek.parts();
+    t[100] = StandardZq::modulo(&(t[100] + 1));
+    let modified = EncryptKey::<StandardZq, 256>::from_parts(a, t).unwrap();
+    assert_ne!(ek.fingerprint(), modified.fingerprint());
+    assert_ne!(ek.to_string(), modified.to_string());
+}
+
+/// Test that random ternary messages round-trip with a large modulus, and invalid symbols
+/// are rejected.
+#[test]
+fn test_encrypt_ternary() {
+    define_zq_i64!(ZqI64_8383489, 8383489);
+
+    let rng = &mut rng();
+    let (ek, dk) = key_gen::<ZqI64_8383489, 512>(rng);
+
+    for len in [0, 1, 100, 512] {
+        let m = (0..len)
+            .map(|_| rand::Rng::random_range(rng, -1..=1))
+            .collect::<Vec<i64>>();
+        let c = ek.encrypt_ternary(rng, m.clone()).unwrap();
+        let decrypted = dk.decrypt_ternary(c);
+        assert_eq!(decrypted[..len], m);
+        assert!(decrypted[len..].iter().all(|mi| *mi == 0));
+    }
+
+    assert_eq!(
+        ek.encrypt_ternary(rng, vec![1, -1, 2]).unwrap_err(),
+        Error::InvalidMessage
+    );
+    assert_eq!(
+        ek.encrypt_ternary(rng, vec![0; 513]).unwrap_err(),
+        Error::InvalidLength
+    );
+}
+
+/// Test that the parameter check passes for the standard field, and reports the computed
+/// values for an under-sized field.
+#[test]
+fn test_check_parameters() {
+    use rlwe_encryption::{check_parameters, ParamError};
+
+    define_zq_i64!(ZqI64_1000, 1000);
+
+    assert_eq!(check_parameters::<StandardZq, 256>(), Ok(()));
+    assert_eq!(check_parameters::<rlwe_encryption::ZqI64, 1024>(), Ok(()));
+    // 2 * 256 * 1 + 1 = 513 >= 1000 / 4 = 250
+    assert_eq!(
+        check_parameters::<ZqI64_1000, 256>(),
+        Err(ParamError::ParametersTooSmall { lhs: 513, rhs: 250 })
+    );
+    assert_eq!(
+        check_parameters::<StandardZq, 100>(),
+        Err(ParamError::NotPowerOfTwo { n: 100 })
+    );
+}
+
+/// Test that a well-formed log verifies, while removing or reordering an entry fails.
+#[test]
+fn test_log_chain() {
+    use rlwe_encryption::{log_append, log_verify_chain};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let mut log = Vec::new();
+    let messages = (0..4)
+        .map(|_| Message::random(rng, 256))
+        .collect::<Vec<_>>();
+    for m in &messages {
+        log_append(&mut log, ek.encrypt(rng, m.clone()));
+    }
+    assert!(log_verify_chain(&log));
+    assert!(log_verify_chain::<StandardZq, 256>(&[]));
+    assert!(format!("{:?}", log[0]).starts_with("LogEntry"));
+    for (m, entry) in messages.iter().zip(log.iter()) {
+        assert_eq!(dk.decrypt(entry.ciphertext.clone()), m.clone().data());
+    }
+
+    let mut removed = log.clone();
+    removed.remove(1);
+    assert!(!log_verify_chain(&removed));
+    let mut removed_first = log.clone();
+    removed_first.remove(0);
+    assert!(!log_verify_chain(&removed_first));
+
+    let mut reordered = log.clone();
+    reordered.swap(1, 2);
+    assert!(!log_verify_chain(&reordered));
+}
+
+/// Test that the sum of two ciphertexts decrypts to the XOR of the two messages.
+#[test]
+fn test_ciphertext_add() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    for _ in 0..10 {
+        let m1 = Message::random(rng, 256);
+        let m2 = Message::random(rng, 256);
+        let xor = m1
+            .clone()
+            .data()
+            .iter()
+            .zip(m2.clone().data().iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+
+        let c1 = ek.encrypt(rng, m1);
+        let c2 = ek.encrypt(rng, m2);
+        assert_eq!(dk.decrypt(c1 + c2), xor);
+    }
+}
+
+/// Test that summing ciphertexts starts from the zero ciphertext and decrypts to the XOR of
+/// all the messages.
+#[test]
+fn test_ciphertext_sum() {
+    use rlwe_encryption::CipherText;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let empty: CipherText<StandardZq, 256> = core::iter::empty().sum();
+    assert_eq!(empty, CipherText::zero());
+    assert_eq!(dk.decrypt(empty), vec![0; 256]);
+
+    let mut xor = vec![0; 256];
+    let mut cs = Vec::new();
+    for _ in 0..5 {
+        let m = Message::random(rng, 256);
+        xor.iter_mut()
+            .zip(m.clone().data())
+            .for_each(|(x, b)| *x ^= b);
+        cs.push(ek.encrypt(rng, m));
+    }
+    assert_eq!(dk.decrypt(cs.iter().cloned().sum()), xor);
+    assert_eq!(
+        dk.decrypt(cs[0].clone() + CipherText::zero()),
+        dk.decrypt(cs[0].clone())
+    );
+}
+
+/// Test that decrypting into a message trims it to the original length.
+#[test]
+fn test_decrypt_message() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 100);
+    let c = ek.encrypt(rng, m.clone());
+    let decrypted = dk.decrypt_message(c, 100);
+    assert_eq!(decrypted.len(), 100);
+    assert_eq!(decrypted, m);
+}
+
+/// Test that decrypting into a message panics if the length is larger than N.
+#[test]
+#[should_panic(expected = "length must be at most N")]
+fn test_decrypt_message_too_long() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 100);
+    let c = ek.encrypt(rng, m);
+    dk.decrypt_message(c, 257);
+}
+
+/// Test that the vectorized modulo is identical to the scalar modulo, including the
+/// extreme values and the lengths not multiple of the number of lanes.
+#[cfg(feature = "simd")]
+#[test]
+fn test_modulo_i32_slice() {
+    use rlwe_encryption::modulo_i32_slice;
+
+    let rng = &mut rng();
+    for len in 0..20 {
+        // both the coefficients of products, and the arbitrary integers
+        let mut xs = (0..len)
+            .map(|_| rand::Rng::random_range(rng, -(1 << 24)..=1 << 24))
+            .collect::<Vec<i32>>();
+        xs.extend((0..len).map(|_| rng.next_u32() as i32));
+        xs.extend([i32::MIN, i32::MAX, 0, -1, 1664, -1664, 1665, 3329]);
+        let expected = xs.iter().map(StandardZq::modulo).collect::<Vec<_>>();
+
+        let mut simd = xs.clone();
+        StandardZq::modulo_slice(&mut simd);
+        assert_eq!(simd, expected);
+
+        // a larger modulus with the rem_euclid implementation
+        let q = 8383489;
+        let mut simd = xs.clone();
+        modulo_i32_slice(&mut simd, q);
+        xs.iter().zip(simd).for_each(|(x, r)| {
+            let a = x.rem_euclid(q);
+            assert_eq!(r, if a > q / 2 { a - q } else { a });
+        });
+    }
+}
+
+/// Test that the fixed-width format round-trips the keys and the ciphertexts, and rejects
+/// truncated input.
+#[test]
+fn test_fixed_width_bytes() {
+    use rlwe_encryption::{standard_i64, CipherText, DecodeError};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+
+    let bytes = c.to_bytes();
+    assert_eq!(bytes.len(), 2 * 256 * 4);
+    assert_eq!(CipherText::from_bytes(&bytes), Ok(c));
+    let bytes = ek.to_bytes();
+    assert_eq!(bytes.len(), 2 * 256 * 4);
+    assert_eq!(EncryptKey::from_bytes(&bytes), Ok(ek.clone()));
+    let bytes = dk.to_bytes();
+    assert_eq!(bytes.len(), 256 * 4);
+    assert_eq!(DecryptKey::from_bytes(&bytes), Ok(dk.clone()));
+
+    // the coefficients are little-endian two's complement
+    assert_eq!(dk.to_bytes()[..4], dk.secret()[0].to_le_bytes());
+
+    let c = ek.encrypt(rng, m.clone());
+    let bytes = c.to_bytes();
+    assert_eq!(
+        CipherText::<StandardZq, 256>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(DecodeError::WrongLength {
+            expected: 2048,
+            actual: 2047
+        })
+    );
+    assert_eq!(
+        DecryptKey::<StandardZq, 256>::from_bytes(&[]),
+        Err(DecodeError::WrongLength {
+            expected: 1024,
+            actual: 0
+        })
+    );
+    // Q is not reduced
+    let mut bytes = ek.to_bytes();
+    bytes[..4].copy_from_slice(&3329i32.to_le_bytes());
+    assert_eq!(
+        EncryptKey::<StandardZq, 256>::from_bytes(&bytes),
+        Err(DecodeError::InvalidCoefficient { index: 0 })
+    );
+
+    let (ek, dk) = standard_i64(rng);
+    let m = m.data().iter().map(|&b| b as i64).collect::<Vec<_>>();
+    let c = ek.encrypt(rng, Message::new(m.clone()));
+    let bytes = c.to_bytes();
+    assert_eq!(bytes.len(), 2 * 1024 * 8);
+    let c = CipherText::from_bytes(&bytes).unwrap();
+    assert_eq!(dk.decrypt(c)[..256], m);
+}
+
+/// Test that the decryption threshold is the one used by the decryption.
+#[test]
+fn test_decrypt_threshold() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let threshold = StandardZq::decrypt_threshold();
+    assert_eq!(threshold, 832);
+    for _ in 0..10 {
+        let m = Message::random(rng, 256);
+        let c = ek.encrypt(rng, m);
+        assert_eq!(
+            dk.decrypt_with_threshold(c.clone(), &threshold),
+            dk.decrypt(c)
+        );
+    }
+}
+
+/// Test that a re-randomized ciphertext decrypts to the same message with different
+/// polynomials.
+#[test]
+fn test_rerandomize() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+    let c1 = ek.rerandomize(rng, c.clone());
+    let c2 = ek.rerandomize(rng, c.clone());
+
+    // the halves of the fixed-width format are `u` and `v`
+    let (bytes, bytes1, bytes2) = (c.to_bytes(), c1.to_bytes(), c2.to_bytes());
+    let (u, v) = bytes.split_at(bytes.len() / 2);
+    let (u1, v1) = bytes1.split_at(bytes.len() / 2);
+    let (u2, v2) = bytes2.split_at(bytes.len() / 2);
+    assert_ne!(u, u1);
+    assert_ne!(v, v1);
+    assert_ne!(u1, u2);
+    assert_ne!(v1, v2);
+    assert_eq!(dk.decrypt(c1), m.clone().data());
+    assert_eq!(dk.decrypt(c2), m.data());
+}
+
+/// Test that the keys of runtime fields round-trip the messages for the parameters which
+/// pass the check.
+#[test]
+fn test_dyn_field_sweep() {
+    use rlwe_encryption::DynField;
+
+    let rng = &mut rng();
+    let mut checked = 0;
+    for (q, b) in [
+        (3329, 1),
+        (7681, 1),
+        (12289, 2),
+        (97, 1),
+        (40961, 4),
+        (8383489, 4),
+    ] {
+        let field = DynField::new(q, b);
+        if field.check_parameters::<256>().is_err() {
+            continue;
+        }
+        checked += 1;
+
+        let (ek, dk) = field.key_gen::<256>(rng);
+        assert_eq!(ek.field(), field);
+        for _ in 0..10 {
+            let m = Message::<StandardZq, 256>::random(rng, 256)
+                .data()
+                .into_iter()
+                .map(i64::from)
+                .collect::<Vec<_>>();
+            let c = ek.encrypt(rng, &m);
+            assert_eq!(dk.decrypt(&c), m);
+        }
+    }
+    assert_eq!(checked, 5);
+}
+
+/// Test that the constant-time equality agrees with the derived equality.
+#[cfg(feature = "constant-time")]
+#[test]
+fn test_ct_eq() {
+    use rlwe_encryption::CipherText;
+    use subtle::ConstantTimeEq;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    assert!(bool::from(dk.ct_eq(&dk.clone())));
+    for i in [0, 100, 255] {
+        let mut s = dk.secret();
+        s[i] = if s[i] == 1 { 0 } else { 1 };
+        let other = DecryptKey::from_parts(s.to_vec()).unwrap();
+        assert!(!bool::from(dk.ct_eq(&other)));
+        assert_ne!(dk, other);
+    }
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m);
+    assert!(bool::from(c.ct_eq(&c.clone())));
+    // change the last coefficient of `v`
+    let mut bytes = c.to_bytes();
+    let last = i32::from_le_bytes(bytes[2044..].try_into().unwrap());
+    bytes[2044..].copy_from_slice(&(if last == 0 { 1i32 } else { 0 }).to_le_bytes());
+    let other = CipherText::<StandardZq, 256>::from_bytes(&bytes).unwrap();
+    assert!(!bool::from(c.ct_eq(&other)));
+    assert_ne!(c, other);
+}
+
+/// Test that the constant-time equality compares the coefficients of a bigint field which
+/// do not fit in `i64` without panicking.
+#[cfg(all(feature = "constant-time", feature = "bigint"))]
+#[test]
+fn test_ct_eq_bigint() {
+    use rlwe_encryption::ZqBig;
+    use subtle::ConstantTimeEq;
+
+    let rng = &mut rng();
+    let (ek, _) = key_gen::<ZqBig, 16>(rng);
+    let m = Message::random(rng, 16);
+    let c = ek.encrypt(rng, m.clone());
+    let other = ek.encrypt(rng, m);
+    assert!(bool::from(c.ct_eq(&c.clone())));
+    assert!(!bool::from(c.ct_eq(&other)));
+}
+
+/// Test that a payload streamed in odd-sized pieces is reassembled after truncation.
+#[test]
+fn test_stream_encryptor() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let mut payload = vec![0u8; 64 * 1024];
+    rng.fill_bytes(&mut payload);
+
+    let mut cs = Vec::new();
+    let mut encryptor = ek.stream_encryptor(rng);
+    let mut rest = &payload[..];
+    for size in [7, 13, 100].into_iter().cycle() {
+        let (piece, tail) = rest.split_at(size.min(rest.len()));
+        encryptor.push(piece);
+        cs.extend(encryptor.take_ciphertexts());
+        rest = tail;
+        if rest.is_empty() {
+            break;
+        }
+    }
+    cs.extend(encryptor.finish());
+    assert_eq!(cs.len(), payload.len().div_ceil(32));
+    assert_eq!(dk.decrypt_bytes(&cs, payload.len()), payload);
+
+    let mut decryptor = dk.stream_decryptor();
+    let mut decrypted = Vec::new();
+    for c in cs {
+        decryptor.push(c);
+        decrypted.extend(decryptor.take_bytes());
+    }
+    decrypted.extend(decryptor.finish());
+    decrypted.truncate(payload.len());
+    assert_eq!(decrypted, payload);
+}
+
+/// Test that a stream encryptor with `N < 8` panics instead of looping on empty chunks.
+#[test]
+#[should_panic(expected = "not a positive multiple of 8")]
+fn test_stream_encryptor_small_n() {
+    let rng = &mut rng();
+    let (ek, _) = key_gen::<StandardZq, 4>(rng);
+    let _ = ek.stream_encryptor(rng);
+}
+
+/// Test that the fallible key generation rejects invalid N and too small parameters.
+#[test]
+fn test_try_key_gen() {
+    use rlwe_encryption::{try_key_gen, ParamError};
+
+    define_zq_i64!(ZqI64_100, 100);
+
+    let rng = &mut rng();
+    assert!(try_key_gen::<StandardZq, 256>(rng).is_ok());
+    assert_eq!(
+        try_key_gen::<StandardZq, 384>(rng).err(),
+        Some(ParamError::NotPowerOfTwo { n: 384 })
+    );
+    assert_eq!(
+        try_key_gen::<ZqI64_100, 256>(rng).err(),
+        Some(ParamError::ParametersTooSmall { lhs: 513, rhs: 25 })
+    );
+}
+
+/// Test that the soft decryption of clean encryptions of 0 and 1 are near 0.0 and 1.0.
+#[test]
+fn test_decrypt_soft() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let zeros = ek.encrypt(rng, Message::new(vec![0; 256]));
+    let soft = dk.decrypt_soft(zeros);
+    assert_eq!(soft.len(), 256);
+    assert!(soft.iter().all(|x| (0.0..0.1).contains(x)));
+
+    let ones = ek.encrypt(rng, Message::new(vec![1; 256]));
+    let soft = dk.decrypt_soft(ones);
+    assert!(soft.iter().all(|x| (0.9..=1.0).contains(x)));
+}
+
+/// Test that each ciphertext of the redundant encryption decrypts under its corresponding key.
+#[test]
+fn test_encrypt_redundant() {
+    use rlwe_encryption::encrypt_redundant;
+
+    let rng = &mut rng();
+    let (eks, dks): (Vec<_>, Vec<_>) = (0..3).map(|_| standard(rng)).unzip();
+
+    let m = Message::random(rng, 256);
+    let cs = encrypt_redundant(rng, &eks, m.clone());
+    assert_eq!(cs.len(), 3);
+    for (dk, c) in dks.iter().zip(cs) {
+        assert_eq!(dk.decrypt(c), m.clone().data());
+    }
+}
+
+/// Test that identical parameters and CRS produce identical digests, and any change alters it.
+#[test]
+fn test_protocol_digest() {
+    use rlwe_encryption::{protocol_digest, Crs};
+
+    define_zq_i64!(ZqI64_8383489, 8383489);
+
+    let rng = &mut rng();
+    let crs = Crs::<StandardZq, 256>::from_label(b"protocol");
+    let (ek, _) = key_gen_with_label::<StandardZq, 256>(b"protocol", rng);
+    assert_eq!(ek.crs(), crs);
+    assert_eq!(protocol_digest(&crs), protocol_digest(&ek.crs()));
+
+    let other_label = Crs::<StandardZq, 256>::from_label(b"other protocol");
+    assert_ne!(protocol_digest(&crs), protocol_digest(&other_label));
+    let other_n = Crs::<StandardZq, 512>::from_label(b"protocol");
+    assert_ne!(protocol_digest(&crs), protocol_digest(&other_n));
+    let other_q = Crs::<ZqI64_8383489, 256>::from_label(b"protocol");
+    assert_ne!(protocol_digest(&crs), protocol_digest(&other_q));
+}
+
+/// Test that a valid MAC verifies, while flipping any coefficient of the ciphertext or
+/// using another key fails the verification.
+#[test]
+fn test_ciphertext_mac() {
+    use rlwe_encryption::CipherText;
+
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+    let key = b"mac key";
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m);
+    let tag = c.mac(key);
+    assert!(c.verify_mac(key, &tag));
+    assert!(!c.verify_mac(b"another key", &tag));
+
+    // flip the lowest bit of each 12-bit coefficient in the compact format
+    let bytes = c.to_compact();
+    for i in 0..512 {
+        let mut tampered = bytes.clone();
+        tampered[i * 12 / 8] ^= 1 << (i * 12 % 8);
+        if let Ok(tampered) = CipherText::<StandardZq, 256>::from_compact(&tampered) {
+            assert!(!tampered.verify_mac(key, &tag));
+        }
+    }
+}
+
+/// Test that converting the polynomials of a key to ndarrays and back preserves the coefficients.
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray() {
+    use rlwe_encryption::{from_ndarray, to_ndarray, EncryptKey, Polynomial};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let [a, t] = ek.to_ndarrays();
+    assert_eq!(a.len(), 256);
+    assert!(a.iter().all(|x| (-1664..=1664).contains(x)));
+    let restored = EncryptKey::<StandardZq, 256>::from_ndarrays(&a, &t).unwrap();
+    assert_eq!(restored.to_ndarrays(), [a.clone(), t]);
+    assert_eq!(restored.to_compact(), ek.to_compact());
+
+    let a_poly: Polynomial<i32, 256> = from_ndarray::<StandardZq, 256>(&a).unwrap();
+    assert_eq!(to_ndarray::<StandardZq, 256>(&a_poly), a);
+    assert!(dk.to_ndarray().iter().all(|x| x.abs() <= 1));
+
+    let too_long = ndarray::Array1::from(vec![0; 257]);
+    assert!(from_ndarray::<StandardZq, 256>(&too_long).is_err());
+}
+
+/// Test that all messages encrypted with a shared `r` still decrypt. Note that the ciphertexts
+/// share the same `a * r`, so their `u` differ only by the small `e2`, i.e. by at most 2B.
+#[test]
+fn test_encrypt_batch_shared_r() {
+    use rlwe_encryption::DecryptKey;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let msgs = (0..5)
+        .map(|_| Message::random(rng, 256))
+        .collect::<Vec<_>>();
+    let cs = ek.encrypt_batch_shared_r(rng, msgs.clone());
+    assert_eq!(cs.len(), msgs.len());
+
+    // u is recovered as the difference of the raw decryptions with the secrets 0 and 1
+    let zero = DecryptKey::<StandardZq, 256>::from_compact(&[0; 384]).unwrap();
+    let mut one_bytes = [0; 384];
+    one_bytes[0] = 1;
+    let one = DecryptKey::<StandardZq, 256>::from_compact(&one_bytes).unwrap();
+    for c in &cs[1..] {
+        let diff = cs[0].add_ciphertext(&c.mul_scalar(&-1));
+        let u_diff = zero
+            .decrypt_raw(diff.clone())
+            .iter()
+            .zip(one.decrypt_raw(diff).iter())
+            .map(|(v, v_u)| StandardZq::modulo(&(v - v_u)))
+            .collect::<Vec<_>>();
+        assert!(u_diff.iter().all(|x| x.abs() <= 2));
+        assert!(u_diff.iter().any(|x| *x != 0));
+    }
+
+    for (m, c) in msgs.into_iter().zip(cs) {
+        assert_eq!(dk.decrypt(c), m.data());
+    }
+}
+
+/// Test the diagnostic messages of misconfigured fields.
+#[test]
+fn test_diagnose_params() {
+    use rlwe_encryption::{diagnose_params, key_gen_diagnosed};
+
+    define_zq_i64!(ZqI64_100, 100);
+    define_zq_i64!(ZqI64_8383488, 8383488);
+
+    let rng = &mut rng();
+    let message = |r: Result<_, Error>| match r {
+        Err(Error::MisconfiguredParams(msg)) => msg,
+        _ => panic!("expected misconfigured parameters"),
+    };
+
+    assert_eq!(
+        message(key_gen_diagnosed::<ZqI64_100, 256>(rng).map(|_| ())),
+        "Q=100 too small for N=256, B=1: need Q > 2052, try Q=2053"
+    );
+    assert_eq!(
+        message(key_gen_diagnosed::<ZqI64_8383488, 512>(rng).map(|_| ())),
+        "Q=8383488 is not a prime, try Q=8383489"
+    );
+    assert_eq!(
+        message(diagnose_params(300, 3329, 1)),
+        "N=300 is not a power of 2, try N=256 or N=512"
+    );
+    assert_eq!(
+        message(diagnose_params(256, 3329, 2)),
+        "Q=3329 too small for N=256, B=2: need Q > 8200, try Q=8209"
+    );
+    assert_eq!(
+        message(diagnose_params(256, u64::MAX, 1)),
+        format!("Q={} is not a prime, no larger prime fits in u64", u64::MAX)
+    );
+    assert!(key_gen_diagnosed::<StandardZq, 256>(rng).is_ok());
+}
+
+/// Test that the noise of the ciphertext encrypted with a noise bound is within the bound.
+#[test]
+fn test_encrypt_bounded_noise() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    // the bound of a fresh encryption is around B * 2 * (2/3 * N) = 341 on average
+    let max_noise = 330;
+    for _ in 0..10 {
+        let m = Message::random(rng, 256);
+        let c = ek
+            .encrypt_bounded_noise(rng, m.clone(), &max_noise)
+            .unwrap();
+
+        // noise = v - u * s - [q/2] m
+        let noise = dk
+            .decrypt_raw(c)
+            .iter()
+            .zip(m.data().iter().chain(std::iter::repeat(&0)))
+            .map(|(x, mi)| StandardZq::modulo(&(x - 1665 * mi)))
+            .collect::<Vec<_>>();
+        assert!(noise.iter().all(|x| x.abs() <= max_noise));
+    }
+
+    let m = Message::random(rng, 256);
+    assert_eq!(
+        ek.encrypt_bounded_noise(rng, m, &0).unwrap_err(),
+        Error::RetryLimitExceeded
+    );
+}
+
+/// Test that converting the symmetric raw decryption to the standard representation matches
+/// the raw decryption in the standard representation.
+#[test]
+fn test_decrypt_raw_standard() {
+    use rlwe_encryption::{to_standard_rep, to_symmetric_rep};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m);
+    let raw = dk.decrypt_raw(c.clone());
+    let raw_standard = dk.decrypt_raw_standard(c);
+
+    assert!(raw.iter().all(|x| (-1664..=1664).contains(x)));
+    assert!(raw_standard.iter().all(|x| (0..3329).contains(x)));
+    assert_eq!(
+        raw.iter()
+            .map(to_standard_rep::<StandardZq>)
+            .collect::<Vec<_>>(),
+        raw_standard
+    );
+    assert_eq!(
+        raw_standard
+            .iter()
+            .map(to_symmetric_rep::<StandardZq>)
+            .collect::<Vec<_>>(),
+        raw
+    );
+}
+
+/// Test that the sparse format is smaller for a sparse ciphertext, falls back to the compact
+/// format for a dense one, and round-trips in both modes.
+#[test]
+fn test_sparse_serialization() {
+    use rlwe_encryption::{ciphertext_size, CipherText};
+
+    let rng = &mut rng();
+    let (ek, _) = standard(rng);
+
+    let mut u = vec![0; 256];
+    u[3] = 5;
+    u[200] = -1664;
+    let sparse = CipherText::<StandardZq, 256>::from_parts(u, vec![0, 0, 1]).unwrap();
+    let bytes = sparse.to_sparse();
+    // tag + 2 counts + 3 entries of 4-byte index and 2-byte value
+    assert_eq!(bytes.len(), 1 + 8 + 3 * 6);
+    assert!(bytes.len() < ciphertext_size::<StandardZq, 256>());
+    let restored = CipherText::<StandardZq, 256>::from_sparse(&bytes).unwrap();
+    assert_eq!(restored.to_compact(), sparse.to_compact());
+
+    let m = Message::random(rng, 256);
+    let dense = ek.encrypt(rng, m);
+    let bytes = dense.to_sparse();
+    assert_eq!(bytes.len(), 1 + ciphertext_size::<StandardZq, 256>());
+    let restored = CipherText::<StandardZq, 256>::from_sparse(&bytes).unwrap();
+    assert_eq!(restored.to_compact(), dense.to_compact());
+
+    assert!(CipherText::<StandardZq, 256>::from_sparse(&[]).is_err());
+    assert!(CipherText::<StandardZq, 256>::from_sparse(&[2]).is_err());
+    let truncated = &sparse.to_sparse()[..10];
+    assert!(CipherText::<StandardZq, 256>::from_sparse(truncated).is_err());
+}
+
+/// Test that the standard field passes the stress test with zero failures.
+#[test]
+fn test_stress_test_field() {
+    use rlwe_encryption::stress_test_field;
+
+    let report = stress_test_field::<StandardZq, 256>(&mut rng(), 200);
+    assert_eq!(report.cycles, 400);
+    assert_eq!(report.failures, 0);
+    assert_eq!(report.bit_errors, 0);
+    assert!(report.passed());
+}
+
+/// Test that the evolved key pair works, but cannot decrypt ciphertexts of the old key.
+#[test]
+fn test_evolve() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+
+    let new_dk = dk.evolve();
+    let new_ek = dk.evolve_encrypt_key(&ek);
+    assert_eq!(new_dk, dk.evolve());
+    assert_ne!(new_dk, dk);
+    assert_ne!(new_dk.decrypt(c.clone()), m.clone().data());
+    assert_eq!(dk.decrypt(c), m.data());
+
+    let m = Message::random(rng, 256);
+    let c = new_ek.encrypt(rng, m.clone());
+    assert_eq!(new_dk.decrypt(c), m.data());
+}
+
+/// Test that the masked sum decrypts to the sum modulo 2 of the selected bits only.
+#[test]
+fn test_masked_sum() {
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let mask = (0..256).map(|i| i % 2 == 0).collect::<Vec<_>>();
+    for _ in 0..10 {
+        let m = Message::random(rng, 256);
+        let expected = m
+            .clone()
+            .data()
+            .iter()
+            .zip(mask.iter())
+            .filter(|(_, &selected)| selected)
+            .map(|(mi, _)| mi)
+            .sum::<i32>()
+            % 2;
+
+        let c = ek.encrypt(rng, m).masked_sum(&mask);
+        assert_eq!(dk.decrypt(c)[0], expected);
+    }
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m);
+    assert!(c.try_masked_sum(&[true; 257]).is_err());
+}
+
+/// Test that the randomly sampled secrets have coefficients in [-B, B].
+#[test]
+fn test_random_secret() {
+    use rlwe_encryption::DecryptKey;
+
+    let rng = &mut rng();
+    for _ in 0..100 {
+        let dk = DecryptKey::<StandardZq, 256>::random_secret(rng);
+        // from_compact rejects secrets with coefficients out of [-B, B]
+        assert!(DecryptKey::<StandardZq, 256>::from_compact(&dk.to_compact()).is_ok());
+    }
+}
+
+/// Test that a ciphertext with more than N coefficients is rejected without panicking.
+#[test]
+#[cfg(feature = "serde")]
+fn test_decrypt_mismatched_length() {
+    use rlwe_encryption::CipherText;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+
+    let too_long = vec![1i32; 257];
+    assert_eq!(
+        CipherText::<StandardZq, 256>::from_parts(too_long.clone(), vec![1]).unwrap_err(),
+        Error::InvalidLength
+    );
+    let c = CipherText::<StandardZq, 256>::from_parts(vec![1; 256], vec![1; 256]).unwrap();
+    assert_eq!(dk.try_decrypt(c).unwrap().len(), 256);
+
+    // a malformed ciphertext from untrusted data is rejected by the deserialization
+    let serialized = bincode::serialize(&(too_long.clone(), too_long)).unwrap();
+    assert!(bincode::deserialize::<CipherText<StandardZq, 256>>(&serialized).is_err());
+    let serialized = bincode::serialize(&(vec![1i32; 256], vec![1i32; 256])).unwrap();
+    let c = bincode::deserialize::<CipherText<StandardZq, 256>>(&serialized).unwrap();
+    assert_eq!(dk.try_decrypt(c).unwrap().len(), 256);
+
+    let m = Message::random(rng, 256);
+    let c = ek.encrypt(rng, m.clone());
+    assert_eq!(dk.try_decrypt(c).unwrap(), m.data());
+}
+
+/// Test that a ciphertext restored from the serde format can be converted to the compact
+/// format and back without data loss.
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde_to_compact() {
+    use rlwe_encryption::CipherText;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+
+    let serialized_c = bincode::serialize(&c).unwrap();
+    let deserialized_c: CipherText<StandardZq, 256> = bincode::deserialize(&serialized_c).unwrap();
+
+    let compact = deserialized_c.to_compact();
+    // 12 bits per coefficient (Q = 3329), 2 polynomials, each with 256 coefficients
+    assert_eq!(compact.len(), 2 * 256 * 12 / 8);
+    let restored = CipherText::<StandardZq, 256>::from_compact(&compact).unwrap();
+    assert_eq!(compact, restored.to_compact());
+    assert_eq!(message.data(), dk.decrypt(restored));
+
+    assert!(CipherText::<StandardZq, 256>::from_compact(&compact[1..]).is_err());
+}
+
+/// Test that keys and ciphertexts round-trip through JSON.
+#[test]
+#[cfg(feature = "json")]
+fn test_json() {
+    use rlwe_encryption::{CipherText, DecryptKey, EncryptKey};
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message.clone());
+
+    let ek_json = ek.to_json();
+    assert!(serde_json::from_str::<serde_json::Value>(&ek_json).is_ok());
+    assert_eq!(ek, EncryptKey::from_json(&ek_json).unwrap());
+
+    let dk_json = dk.to_json();
+    assert!(serde_json::from_str::<serde_json::Value>(&dk_json).is_ok());
+    assert_eq!(dk, DecryptKey::from_json(&dk_json).unwrap());
+
+    let c_json = c.to_json();
+    assert!(serde_json::from_str::<serde_json::Value>(&c_json).is_ok());
+    let c2 = CipherText::from_json(&c_json).unwrap();
+    assert_eq!(c, c2);
+    assert_eq!(message.data(), dk.decrypt(c2));
+
+    assert_eq!(
+        CipherText::<StandardZq, 256>::from_json("{"),
+        Err(Error::InvalidJson)
+    );
+}
+
+/// Test that the envelope round-trips both the ciphertext and its metadata.
+#[test]
+fn test_ciphertext_envelope() {
+    use rlwe_encryption::CipherTextEnvelope;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 100);
+    let c = ek.encrypt(rng, message.clone());
+
+    let envelope = CipherTextEnvelope::new(c, b"sender=alice;len=100".to_vec());
+    let bytes = envelope.to_bytes();
+    let restored = CipherTextEnvelope::<StandardZq, 256>::from_bytes(&bytes).unwrap();
+    assert_eq!(restored.metadata, envelope.metadata);
+    assert_eq!(bytes, restored.to_bytes());
+    assert_eq!(message.data(), dk.decrypt_exact(restored.ciphertext, 100));
+
+    assert!(CipherTextEnvelope::<StandardZq, 256>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    #[cfg(feature = "serde")]
+    {
+        let serialized = bincode::serialize(&envelope).unwrap();
+        let deserialized: CipherTextEnvelope<StandardZq, 256> =
+            bincode::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized.metadata, envelope.metadata);
+        assert_eq!(
+            deserialized.ciphertext.to_compact(),
+            envelope.ciphertext.to_compact()
+        );
+    }
+}
+
+/// Test that keys can be stored through the codec trait with different formats.
+#[test]
+fn test_key_codec() {
+    use rlwe_encryption::{CompactCodec, DecryptKey, KeyCodec};
+
+    fn roundtrip<C: KeyCodec<DecryptKey<StandardZq, 256>>>(dk: &DecryptKey<StandardZq, 256>) {
+        let bytes = C::encode(dk);
+        assert_eq!(dk, &C::decode(&bytes).unwrap());
+    }
+
+    let (_, dk) = standard(&mut rng());
+    roundtrip::<CompactCodec>(&dk);
+    #[cfg(feature = "json")]
+    roundtrip::<rlwe_encryption::JsonCodec>(&dk);
+}
+
+/// Test that a plaintext multiplication consumes more noise budget than additions alone.
+#[test]
+#[cfg(feature = "noise-tracking")]
+fn test_noise_tracking() {
+    use rlwe_encryption::NoiseTracked;
+
+    let rng = &mut rng();
+    let (ek, dk) = key_gen::<StandardZq, 16>(rng);
+    let m1 = Message::new(vec![1, 0, 1]);
+    let m2 = Message::new(vec![1, 1, 0]);
+    let c1 = NoiseTracked::new(ek.encrypt(rng, m1));
+    let c2 = NoiseTracked::new(ek.encrypt(rng, m2));
+    let fresh = c1.noise_bound();
+    assert_eq!(fresh, 2.0 * 16.0 + 1.0);
+
+    let added = c1.add(&c2).add(&c2);
+    let multiplied = c1.mul_plaintext(&[1, 1]).add(&c2).add(&c2);
+    assert_eq!(added.noise_bound(), 3.0 * fresh);
+    assert!(multiplied.noise_bound() > added.noise_bound());
+    assert!(multiplied.remaining_budget() > 0.0);
+
+    // (1 + x^2) * (1 + x) + 2 * (1 + x) = 1 + x + x^2 + x^3 (mod 2)
+    let m = dk.decrypt_exact(multiplied.into_inner(), 4);
+    assert_eq!(m, vec![1, 1, 1, 1]);
+    // (1 + x^2) + 2 * (1 + x) = 1 + x^2 (mod 2)
+    let m = dk.decrypt_exact(added.into_inner(), 3);
+    assert_eq!(m, vec![1, 0, 1]);
+}
+
+/// Test that adversarial inputs to the fallible entry points return errors instead of panicking.
+#[test]
+fn test_adversarial_inputs() {
+    use rlwe_encryption::{CipherText, DecryptKey, EncryptKey};
+    type M = Message<StandardZq, 256>;
+
+    let rng = &mut rng();
+    let (ek, dk) = standard(rng);
+    let message = Message::random(rng, 256);
+    let c = ek.encrypt(rng, message);
+
+    assert_eq!(M::try_new(vec![0; 257]), Err(Error::InvalidMessage));
+    assert_eq!(M::try_new(vec![0, 2]), Err(Error::InvalidMessage));
+    assert_eq!(M::try_new(vec![-1]), Err(Error::InvalidMessage));
+    assert_eq!(M::try_random(rng, 257), Err(Error::InvalidLength));
+
+    for bytes in [
+        &[][..],
+        &[1, 0, 0],
+        &[0xff; 4],
+        &[9, 0, 0, 0, 0xff],
+        &[0xff; 100],
+    ] {
+        assert!(M::from_bytes(bytes).is_err());
+        assert!(CipherText::<StandardZq, 256>::from_compact(bytes).is_err());
+        assert!(EncryptKey::<StandardZq, 256>::from_compact(bytes).is_err());
+        assert!(DecryptKey::<StandardZq, 256>::from_compact(bytes).is_err());
+    }
+    // coefficients out of range
+    assert!(CipherText::<StandardZq, 256>::from_compact(&[0xff; 768]).is_err());
+    assert!(key_gen_from_seed::<StandardZq, 256>([7; 32]).is_err());
+
+    assert_eq!(
+        dk.try_decrypt_exact(c.clone(), 257),
+        Err(Error::InvalidLength)
+    );
+    assert!(ek.try_encrypt_one_hot(rng, 10, 10).is_err());
+    assert!(ek.try_encrypt_one_hot(rng, 0, 257).is_err());
+    assert!(c.try_mul_plaintext(&[1; 257]).is_err());
+}